@@ -0,0 +1,147 @@
+use shared::catrobat::{CatrobatProject, import_catrobat, import_code_xml};
+use shared::raster::{CSS_DPI, PngOptions, render_svg_png};
+use shared::script::Script;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+// Renders every script of a Catrobat project, a .catrobat archive or its
+// code.xml, to its own file:
+//
+//   <out-dir>/<scene>/<object>/script-1.svg
+//
+// The output goes next to the project unless --out-dir is given, --png
+// rasterizes the scripts at three pixels per SVG unit like the frontend.
+// Bricks the catalog does not know are rendered as placeholders and listed at
+// the end; with --strict they fail the run.
+
+const USAGE: &str =
+    "usage: render_catrobat <project.catrobat|code.xml> [--out-dir <directory>] [--png] [--strict]";
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let mut project = None;
+    let mut out_dir = None;
+    let mut png = false;
+    let mut strict = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out-dir" => out_dir = args.next().map(PathBuf::from),
+            "--png" => png = true,
+            "--strict" => strict = true,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            _ if project.is_none() => project = Some(PathBuf::from(arg)),
+            _ => {
+                eprintln!("{USAGE}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    let Some(project) = project else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+
+    let imported = match read_project(&project) {
+        Ok(imported) => imported,
+        Err(error) => {
+            eprintln!("{}: {error}", project.display());
+            return ExitCode::FAILURE;
+        }
+    };
+    let out_dir = out_dir.unwrap_or_else(|| {
+        project
+            .parent()
+            .map_or_else(PathBuf::new, Path::to_path_buf)
+    });
+
+    let mut total = 0;
+    let mut failures = 0;
+    for scene in &imported.scenes {
+        for object in &scene.objects {
+            for (index, script) in object.scripts.iter().enumerate() {
+                total += 1;
+                let extension = if png { "png" } else { "svg" };
+                let target = out_dir
+                    .join(file_name(&scene.name))
+                    .join(file_name(&object.name))
+                    .join(format!("script-{}.{extension}", index + 1));
+                match render_script(script, &target, png) {
+                    Ok(()) => println!("{}", target.display()),
+                    Err(error) => {
+                        failures += 1;
+                        eprintln!("{}: {error}", target.display());
+                    }
+                }
+            }
+        }
+    }
+
+    for unknown in &imported.unknown_bricks {
+        eprintln!("warning: {unknown}");
+    }
+    println!(
+        "rendered {} of {total} scripts, {} unknown bricks",
+        total - failures,
+        imported.unknown_bricks.len()
+    );
+    let unknown_failed = strict && !imported.unknown_bricks.is_empty();
+    if failures == 0 && !unknown_failed {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn read_project(path: &Path) -> Result<CatrobatProject, String> {
+    let bytes = std::fs::read(path).map_err(|error| error.to_string())?;
+    let is_xml = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("xml"));
+    if is_xml {
+        let text = String::from_utf8(bytes).map_err(|error| error.to_string())?;
+        import_code_xml(&text)
+    } else {
+        import_catrobat(&bytes)
+    }
+    .map_err(|error| error.to_string())
+}
+
+// scene and object names are free text, keep them usable as directory names
+fn file_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let name = name.trim().trim_start_matches('.');
+    if name.is_empty() {
+        "unnamed".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+fn render_script(script: &Script, target: &Path, png: bool) -> Result<(), String> {
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+    }
+    let svg = script.to_svg_string();
+    let data = if png {
+        let options = PngOptions {
+            width: None,
+            dpi: Some(3.0 * CSS_DPI),
+        };
+        render_svg_png(&svg, script.size(), &options).map_err(|error| error.to_string())?
+    } else {
+        svg.into_bytes()
+    };
+    std::fs::write(target, data).map_err(|error| error.to_string())
+}
@@ -0,0 +1,119 @@
+use serde::Deserialize;
+use shared::document::BrickSpec;
+use shared::raster::{PngOptions, render_png};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+// Renders every brick listed in a JSON manifest to its own SVG file:
+//
+// [
+//   { "type": "H1Base", "scheme": "Blue", "content": "When tapped", "output": "when_tapped.svg" }
+// ]
+//
+// Output paths are relative to the manifest unless --out-dir is given. Entries
+// whose output ends in ".png" are rasterized, optionally with "png_width" or
+// "dpi". "width" and "height" set the size of the brick itself.
+
+const USAGE: &str = "usage: render_manifest <manifest.json> [--out-dir <directory>]";
+
+#[derive(Deserialize)]
+struct ManifestEntry {
+    #[serde(flatten)]
+    brick: BrickSpec,
+    output: PathBuf,
+    png_width: Option<u32>,
+    dpi: Option<f32>,
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let mut manifest = None;
+    let mut out_dir = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out-dir" => out_dir = args.next().map(PathBuf::from),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            _ if manifest.is_none() => manifest = Some(PathBuf::from(arg)),
+            _ => {
+                eprintln!("{USAGE}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    let Some(manifest) = manifest else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+
+    let entries = match read_manifest(&manifest) {
+        Ok(entries) => entries,
+        Err(error) => {
+            eprintln!("{}: {error}", manifest.display());
+            return ExitCode::FAILURE;
+        }
+    };
+    let out_dir = out_dir.unwrap_or_else(|| {
+        manifest
+            .parent()
+            .map_or_else(PathBuf::new, Path::to_path_buf)
+    });
+
+    let total = entries.len();
+    let mut failures = 0;
+    for (index, entry) in entries.into_iter().enumerate() {
+        // entries are parsed one by one so a typo only fails its own brick
+        let entry: ManifestEntry = match serde_json::from_value(entry) {
+            Ok(entry) => entry,
+            Err(error) => {
+                failures += 1;
+                eprintln!("entry {index}: {error}");
+                continue;
+            }
+        };
+        let target = out_dir.join(&entry.output);
+        match render_entry(&entry, &target) {
+            Ok(()) => println!("{}", target.display()),
+            Err(error) => {
+                failures += 1;
+                eprintln!("entry {index} ({}): {error}", entry.output.display());
+            }
+        }
+    }
+
+    println!("rendered {} of {total} bricks", total - failures);
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn read_manifest(path: &Path) -> Result<Vec<serde_json::Value>, String> {
+    let text = std::fs::read_to_string(path).map_err(|error| error.to_string())?;
+    serde_json::from_str(&text).map_err(|error| error.to_string())
+}
+
+fn render_entry(entry: &ManifestEntry, target: &Path) -> Result<(), String> {
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+    }
+
+    let brick = entry.brick.to_brick();
+    let color_scheme = &entry.brick.color_scheme;
+    let is_png = target
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("png"));
+    let data = if is_png {
+        let options = PngOptions {
+            width: entry.png_width,
+            dpi: entry.dpi,
+        };
+        render_png(brick.as_ref(), color_scheme, &options).map_err(|error| error.to_string())?
+    } else {
+        brick.to_svg_string(color_scheme).into_bytes()
+    };
+    std::fs::write(target, data).map_err(|error| error.to_string())
+}
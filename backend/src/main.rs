@@ -1,3 +1,347 @@
+use shared::brick::geometry::MAX_BRICK_SIZE;
+use shared::category::BrickCategory;
+use shared::catrobat::export_catrobat;
+use shared::color::{BLUE_SCHEME, find_color_scheme};
+use shared::document::BrickSpec;
+use shared::raster::{PngOptions, render_png, render_svg_png};
+use shared::script::{Script, ScriptBrick};
+use shared::types::BrickType;
+use std::io::Read;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+// The service only binds to the loopback interface by default and needs no
+// network access, so the documentation pipeline can run it offline.
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+
+const USAGE: &str = "BrickCreator backend
+
+GET /render.svg?type=<brick type>&scheme=<color scheme>&content=<text>&width=<w>&height=<h>
+GET /render.svg?type=<brick type>&category=<brick category>&content=<text>
+GET /render.png?type=<brick type>&scheme=<color scheme>&content=<text>&png_width=<px>&dpi=<dpi>
+
+  type       H0Collapsed, H1Base, H2Base, H3Base, H1Control, H2Control,
+             If, IfElse, Repeat or Forever
+  scheme     name of a color scheme, e.g. Blue or dark-blue (default: Blue)
+  category   Catrobat brick category, e.g. Motion or Looks, for its color scheme
+  content    text shown on the brick (default: empty)
+  width      brick width in SVG units, at most 10000 (default: width of the type)
+  height     brick height in SVG units, at most 10000 (default: fitted to the
+             content)
+  png_width  PNG width in pixels, takes precedence over dpi
+  dpi        PNG resolution, 96 renders one pixel per SVG unit (default: 96)
+
+POST /render.svg
+POST /render.png?png_width=<px>&dpi=<dpi>
+
+  body       a brick as JSON: {\"type\": \"H1Base\", \"scheme\": \"Blue\", \"content\": \"Hello\"}
+
+POST /script.svg
+POST /script.png?png_width=<px>&dpi=<dpi>
+
+  body       a script as JSON: {\"bricks\": [<brick>, ...]}, C-shaped bricks list
+             the bricks in their slots as \"slots\": [[<brick>, ...], ...]
+
+POST /script.xml?name=<object name>
+POST /script.catrobat?name=<object name>
+
+  body       a script as JSON, exported as the code.xml of a Catrobat project or
+             as a whole project Pocket Code can open
+  name       name of the project and of the object with the script
+             (default: BrickCreator)
+";
+
+const DEFAULT_EXPORT_NAME: &str = "BrickCreator";
+// bricks and scripts are small, larger bodies are refused with 413
+const MAX_BODY_BYTES: usize = 1 << 20;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Svg,
+    Png,
+}
+
+struct Rendered {
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
 fn main() {
-    println!("Hello, world!");
+    let address = std::env::args()
+        .nth(1)
+        .or_else(|| std::env::var("BRICK_CREATOR_ADDRESS").ok())
+        .unwrap_or_else(|| DEFAULT_ADDRESS.to_string());
+
+    let server = match Server::http(&address) {
+        Ok(server) => server,
+        Err(error) => {
+            eprintln!("could not listen on {address}: {error}");
+            std::process::exit(1);
+        }
+    };
+    println!("BrickCreator backend listening on http://{address}");
+
+    for request in server.incoming_requests() {
+        handle(request);
+    }
+}
+
+fn handle(mut request: Request) {
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+
+    let result = match (request.method(), path) {
+        (Method::Get, "/") => Ok(Rendered {
+            content_type: "text/plain; charset=utf-8",
+            body: USAGE.as_bytes().to_vec(),
+        }),
+        (Method::Get, "/render.svg") => render_query(query, Format::Svg),
+        (Method::Get, "/render.png") => render_query(query, Format::Png),
+        (Method::Post, "/render.svg") => render_body(&mut request, query, Format::Svg),
+        (Method::Post, "/render.png") => render_body(&mut request, query, Format::Png),
+        (Method::Post, "/script.svg") => render_script(&mut request, query, Format::Svg),
+        (Method::Post, "/script.png") => render_script(&mut request, query, Format::Png),
+        (Method::Post, "/script.xml") => export_script(&mut request, query, false),
+        (Method::Post, "/script.catrobat") => export_script(&mut request, query, true),
+        _ => Err((404, format!("no route for {} {}", request.method(), path))),
+    };
+
+    let response = match result {
+        Ok(rendered) => Response::from_data(rendered.body)
+            .with_header(header("Content-Type", rendered.content_type)),
+        Err((status, message)) => Response::from_data(message.into_bytes())
+            .with_status_code(status)
+            .with_header(header("Content-Type", "text/plain; charset=utf-8")),
+    };
+    let response = response.with_header(header("Access-Control-Allow-Origin", "*"));
+
+    if let Err(error) = request.respond(response) {
+        eprintln!("failed to send response: {error}");
+    }
+}
+
+fn render_query(query: &str, format: Format) -> Result<Rendered, (u16, String)> {
+    let mut brick_type = None;
+    let mut scheme = None;
+    let mut category = None;
+    let mut content = String::new();
+    let (mut width, mut height) = (None, None);
+    let mut png_options = PngOptions::default();
+
+    for (key, value) in form_urlencoded::parse(query.as_bytes()) {
+        match (key.as_ref(), format) {
+            ("type", _) => brick_type = Some(value.into_owned()),
+            ("scheme", _) => scheme = Some(value.into_owned()),
+            ("category", _) => category = Some(value.into_owned()),
+            ("content", _) => content = value.into_owned(),
+            ("width", _) => width = Some(parse_size(&key, &value)?),
+            ("height", _) => height = Some(parse_size(&key, &value)?),
+            ("png_width" | "dpi", Format::Png) => parse_png_option(&mut png_options, &key, &value)?,
+            (other, _) => return Err((400, format!("unknown parameter '{other}'"))),
+        }
+    }
+
+    let brick_type = brick_type.ok_or((400, "missing parameter 'type'".to_string()))?;
+    let brick_type = BrickType::from_id(&brick_type)
+        .ok_or_else(|| (400, format!("unknown brick type '{brick_type}'")))?;
+    // an explicit scheme wins over the colors of the category
+    let color_scheme = match (scheme, category) {
+        (Some(scheme), _) => find_color_scheme(&scheme)
+            .ok_or_else(|| (400, format!("unknown color scheme '{scheme}'")))?,
+        (None, Some(category)) => BrickCategory::from_id(&category)
+            .ok_or_else(|| (400, format!("unknown brick category '{category}'")))?
+            .color_scheme(),
+        (None, None) => BLUE_SCHEME,
+    };
+
+    render(
+        &BrickSpec::new(brick_type, color_scheme, &content).with_size(width, height),
+        &png_options,
+        format,
+    )
+}
+
+fn render_body(
+    request: &mut Request,
+    query: &str,
+    format: Format,
+) -> Result<Rendered, (u16, String)> {
+    let png_options = parse_png_query(query, format)?;
+    let spec: BrickSpec = serde_json::from_str(&read_body(request)?)
+        .map_err(|error| (400, format!("invalid brick: {error}")))?;
+    check_brick_size(&spec)?;
+
+    render(&spec, &png_options, format)
+}
+
+fn render_script(
+    request: &mut Request,
+    query: &str,
+    format: Format,
+) -> Result<Rendered, (u16, String)> {
+    let png_options = parse_png_query(query, format)?;
+    let script: Script = serde_json::from_str(&read_body(request)?)
+        .map_err(|error| (400, format!("invalid script: {error}")))?;
+    check_script_sizes(&script.bricks)?;
+
+    let svg = script.to_svg_string();
+    match format {
+        Format::Svg => Ok(Rendered {
+            content_type: "image/svg+xml",
+            body: svg.into_bytes(),
+        }),
+        Format::Png => Ok(Rendered {
+            content_type: "image/png",
+            body: render_svg_png(&svg, script.size(), &png_options)
+                .map_err(|error| (400, error.to_string()))?,
+        }),
+    }
+}
+
+// bricks without a Catrobat counterpart end up as notes, the warnings are
+// comments in code.xml
+fn export_script(
+    request: &mut Request,
+    query: &str,
+    archive: bool,
+) -> Result<Rendered, (u16, String)> {
+    let mut name = DEFAULT_EXPORT_NAME.to_string();
+    for (key, value) in form_urlencoded::parse(query.as_bytes()) {
+        match key.as_ref() {
+            "name" if !value.trim().is_empty() => name = value.trim().to_string(),
+            "name" => return Err((400, "parameter 'name' must not be empty".to_string())),
+            other => return Err((400, format!("unknown parameter '{other}'"))),
+        }
+    }
+    let script: Script = serde_json::from_str(&read_body(request)?)
+        .map_err(|error| (400, format!("invalid script: {error}")))?;
+    check_script_sizes(&script.bricks)?;
+
+    let export = export_catrobat(&script, &name);
+    if archive {
+        Ok(Rendered {
+            content_type: "application/zip",
+            body: export
+                .to_archive()
+                .map_err(|error| (500, error.to_string()))?,
+        })
+    } else {
+        Ok(Rendered {
+            content_type: "application/xml; charset=utf-8",
+            body: export.code_xml.into_bytes(),
+        })
+    }
+}
+
+fn parse_png_query(query: &str, format: Format) -> Result<PngOptions, (u16, String)> {
+    let mut png_options = PngOptions::default();
+    for (key, value) in form_urlencoded::parse(query.as_bytes()) {
+        match (key.as_ref(), format) {
+            ("png_width" | "dpi", Format::Png) => {
+                parse_png_option(&mut png_options, &key, &value)?;
+            }
+            (other, _) => return Err((400, format!("unknown parameter '{other}'"))),
+        }
+    }
+    Ok(png_options)
+}
+
+// Reads the request body, refusing bodies above `MAX_BODY_BYTES` before
+// parsing them.
+fn read_body(request: &mut Request) -> Result<String, (u16, String)> {
+    let too_large = || {
+        (
+            413,
+            format!("request body is larger than {MAX_BODY_BYTES} bytes"),
+        )
+    };
+    if request
+        .body_length()
+        .is_some_and(|length| length > MAX_BODY_BYTES)
+    {
+        return Err(too_large());
+    }
+    let mut body = String::new();
+    Read::take(request.as_reader(), MAX_BODY_BYTES as u64 + 1)
+        .read_to_string(&mut body)
+        .map_err(|error| (400, format!("could not read request body: {error}")))?;
+    if body.len() > MAX_BODY_BYTES {
+        return Err(too_large());
+    }
+    Ok(body)
+}
+
+fn render(
+    spec: &BrickSpec,
+    png_options: &PngOptions,
+    format: Format,
+) -> Result<Rendered, (u16, String)> {
+    match format {
+        Format::Svg => Ok(Rendered {
+            content_type: "image/svg+xml",
+            body: spec.to_svg_string().into_bytes(),
+        }),
+        Format::Png => Ok(Rendered {
+            content_type: "image/png",
+            body: render_png(spec.to_brick().as_ref(), &spec.color_scheme, png_options)
+                .map_err(|error| (400, error.to_string()))?,
+        }),
+    }
+}
+
+fn parse_png_option(options: &mut PngOptions, key: &str, value: &str) -> Result<(), (u16, String)> {
+    if key == "png_width" {
+        options.width = Some(parse_number(key, value)?);
+    } else {
+        options.dpi = Some(parse_number(key, value)?);
+    }
+    Ok(())
+}
+
+fn parse_size(key: &str, value: &str) -> Result<f32, (u16, String)> {
+    let size: f32 = parse_number(key, value)?;
+    check_size(key, size)?;
+    Ok(size)
+}
+
+fn check_size(key: &str, size: f32) -> Result<(), (u16, String)> {
+    if !size.is_finite() || size <= 0.0 {
+        return Err((400, format!("parameter '{key}' must be positive: '{size}'")));
+    }
+    if size > MAX_BRICK_SIZE {
+        return Err((
+            400,
+            format!("parameter '{key}' must be at most {MAX_BRICK_SIZE}: '{size}'"),
+        ));
+    }
+    Ok(())
+}
+
+// the sizes of a brick from a JSON body, checked like the query parameters
+fn check_brick_size(spec: &BrickSpec) -> Result<(), (u16, String)> {
+    for (key, size) in [("width", spec.width), ("height", spec.height)] {
+        if let Some(size) = size {
+            check_size(key, size)?;
+        }
+    }
+    Ok(())
+}
+
+fn check_script_sizes(bricks: &[ScriptBrick]) -> Result<(), (u16, String)> {
+    for script_brick in bricks {
+        check_brick_size(&script_brick.brick)?;
+        for slot in &script_brick.slots {
+            check_script_sizes(slot)?;
+        }
+    }
+    Ok(())
+}
+
+fn parse_number<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, (u16, String)> {
+    value
+        .parse()
+        .map_err(|_| (400, format!("parameter '{key}' is not a number: '{value}'")))
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name, value).expect("static header is valid")
 }
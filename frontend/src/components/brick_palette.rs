@@ -0,0 +1,161 @@
+use crate::drag::{DragSource, start_drag};
+use shared::catalog::{CatalogBrick, search_catalog};
+use shared::category::ALL_BRICK_CATEGORIES;
+use shared::types::{ALL_BRICK_TYPES, BrickType};
+use web_sys::HtmlInputElement;
+use yew::{
+    Callback, DragEvent, Html, InputEvent, Properties, TargetCast, classes, function_component,
+    html, use_state,
+};
+
+fn brick_type_name(brick_type: &BrickType) -> &str {
+    match brick_type {
+        BrickType::H0Collapsed => "H0 Collapsed",
+        BrickType::H1Base => "H1 Base",
+        BrickType::H1Control => "H1 Control",
+        BrickType::H2Base => "H2 Base",
+        BrickType::H2Control => "H2 Control",
+        BrickType::H3Base => "H3 Base",
+        BrickType::If => "If",
+        BrickType::IfElse => "If Else",
+        BrickType::Repeat => "Repeat",
+        BrickType::Forever => "Forever",
+    }
+}
+
+fn brick_type_description(brick_type: &BrickType) -> &str {
+    match brick_type {
+        BrickType::H0Collapsed => "Thin horizontal brick",
+        BrickType::H1Base => "Standard base brick - small",
+        BrickType::H1Control => "Control brick - small",
+        BrickType::H2Base => "Standard base brick - medium",
+        BrickType::H2Control => "Control brick - medium",
+        BrickType::H3Base => "Standard base brick - large",
+        BrickType::If => "C-shaped brick - if, end if",
+        BrickType::IfElse => "C-shaped brick - if, else, end if",
+        BrickType::Repeat => "C-shaped brick - repeat, end of loop",
+        BrickType::Forever => "C-shaped brick - endless loop",
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct BrickPaletteProps {
+    // the id of a catalog brick was picked
+    pub on_select_brick: Callback<String>,
+    // a bare shape was picked, keeping the content of the panel
+    pub on_select_type: Callback<BrickType>,
+    #[prop_or(BrickType::H0Collapsed)]
+    pub selected: BrickType,
+    // content of the panel brick, to mark the catalog brick it came from
+    #[prop_or_default]
+    pub content: String,
+}
+
+fn catalog_item(brick: &CatalogBrick, props: &BrickPaletteProps) -> Html {
+    let source = DragSource::Catalog(brick.id.clone());
+    let (on_select, id) = (props.on_select_brick.clone(), brick.id.clone());
+    let is_selected = props.selected == brick.brick_type && props.content == brick.content;
+    html! {
+        <div
+            class={classes!("brick-type-item", is_selected.then_some("selected"))}
+            style={format!("border-left-color: {};", brick.category.color_scheme().color.to_css())}
+            title={brick.content.clone()}
+            onclick={move |_| on_select.emit(id.clone())}
+            draggable="true"
+            ondragstart={move |event: DragEvent| start_drag(&event, &source)}
+        >
+            <div class="brick-type-name">{ brick.name.clone() }</div>
+            <div class="brick-type-description">{ brick.description.clone() }</div>
+        </div>
+    }
+}
+
+fn shape_item(brick_type: BrickType, props: &BrickPaletteProps) -> Html {
+    let source = DragSource::Palette(brick_type);
+    let on_select = props.on_select_type.clone();
+    let is_selected = props.selected == brick_type;
+    html! {
+        <div
+            class={classes!("brick-type-item", is_selected.then_some("selected"))}
+            onclick={move |_| on_select.emit(brick_type)}
+            draggable="true"
+            ondragstart={move |event: DragEvent| start_drag(&event, &source)}
+        >
+            <div class="brick-type-name">{ brick_type_name(&brick_type) }</div>
+            <div class="brick-type-description">{ brick_type_description(&brick_type) }</div>
+        </div>
+    }
+}
+
+// The bricks of the catalog grouped by category, and the bare shapes at the
+// end. The search filters both.
+#[function_component(BrickPalette)]
+pub fn brick_palette(props: &BrickPaletteProps) -> Html {
+    let query = use_state(String::new);
+    let on_search = {
+        let query = query.clone();
+        move |event: InputEvent| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            query.set(input.value());
+        }
+    };
+
+    let bricks = search_catalog(&query);
+    let terms = query.to_lowercase();
+    let shapes: Vec<BrickType> = ALL_BRICK_TYPES
+        .iter()
+        .copied()
+        .filter(|brick_type| {
+            let text = format!(
+                "{} {} {}",
+                brick_type_name(brick_type),
+                brick_type_description(brick_type),
+                brick_type.id()
+            )
+            .to_lowercase();
+            terms.split_whitespace().all(|term| text.contains(term))
+        })
+        .collect();
+
+    html! {
+        <div class="brick-type-list">
+            <h2>{ "Bricks" }</h2>
+            <input
+                class="brick-palette-search"
+                type="search"
+                placeholder="Search bricks, e.g. repeat or sound"
+                value={(*query).clone()}
+                oninput={on_search}
+            />
+            {for ALL_BRICK_CATEGORIES.iter().map(|category| {
+                let items: Vec<&CatalogBrick> = bricks
+                    .iter()
+                    .copied()
+                    .filter(|brick| brick.category == *category)
+                    .collect();
+                if items.is_empty() {
+                    return Html::default();
+                }
+                html! {
+                    <div class="brick-palette-group">
+                        <h3>{ category.name() }</h3>
+                        <div class="brick-type-grid">
+                            {for items.iter().map(|brick| catalog_item(brick, props))}
+                        </div>
+                    </div>
+                }
+            })}
+            if !shapes.is_empty() {
+                <div class="brick-palette-group">
+                    <h3>{ "Shapes" }</h3>
+                    <div class="brick-type-grid">
+                        {for shapes.iter().map(|brick_type| shape_item(*brick_type, props))}
+                    </div>
+                </div>
+            }
+            if bricks.is_empty() && shapes.is_empty() {
+                <div class="panel-hint">{ format!("No bricks match '{}'", *query) }</div>
+            }
+        </div>
+    }
+}
@@ -1,44 +1,228 @@
-use shared::color::{ALL_COLOR_SCHEMES, ColorScheme};
-use yew::{Callback, Html, Properties, function_component, html};
+use shared::color::contrast::{ContrastLevel, MIN_TEXT_CONTRAST, check_schemes, text_contrast};
+use shared::color::{ALL_COLOR_SCHEMES, BLUE_SCHEME, Color, ColorScheme, find_color_scheme};
+use std::borrow::Cow;
+use web_sys::HtmlInputElement;
+use yew::{
+    Callback, Html, InputEvent, MouseEvent, Properties, TargetCast, classes, function_component,
+    html, use_state,
+};
+
+// a color of the scheme the picker edits
+type SchemeColor = fn(&mut ColorScheme) -> &mut Color;
 
 #[derive(Properties, PartialEq)]
 pub struct ColorViewProps {
+    #[prop_or_default]
+    pub custom_schemes: Vec<ColorScheme>,
     pub on_select: Callback<ColorScheme>,
+    // a custom scheme was saved, replacing the one with the same name
+    pub on_save: Callback<ColorScheme>,
+    pub on_delete: Callback<ColorScheme>,
+}
+
+// contrast of the text on the body, flagged when it is too hard to read
+fn contrast_badge(scheme: &ColorScheme) -> Html {
+    let ratio = text_contrast(scheme);
+    let readable = ratio >= MIN_TEXT_CONTRAST;
+    html! {
+        <div
+            class={classes!("color-contrast", (!readable).then_some("low"))}
+            title="WCAG contrast of the text on the brick"
+        >
+            { format!("{ratio:.1}:1 {}", ContrastLevel::of(ratio).label()) }
+        </div>
+    }
+}
+
+fn scheme_item(scheme: &ColorScheme, onclick: Callback<MouseEvent>, delete: Html) -> Html {
+    html! {
+        <div class="color-item" onclick={onclick} style="cursor: pointer;">
+            <div class="color-name">{ scheme.name.to_string() }{delete}</div>
+            <div class="color-description-row">
+                <div class="color-description"> {"Background"} </div>
+                <div class="color-description"> {"Border"} </div>
+            </div>
+            <div class="color-preview">
+                <div class="color-sample" style={format!(
+                    "background: {}; border: 1px solid {}; color: {};",
+                    scheme.color.to_css(), scheme.border.to_css(), scheme.text.to_css()
+                )}>{ "Abc" }</div>
+                <div class="color-sample-shade" style={format!(
+                    "background: {}; border: 1px solid {};",
+                    scheme.shade.to_css(), scheme.border.to_css()
+                )}></div>
+            </div>
+            {contrast_badge(scheme)}
+        </div>
+    }
 }
 
 #[function_component(ColorView)]
 pub fn color_view(props: &ColorViewProps) -> Html {
+    // the picker starts from the scheme clicked last
+    let draft = use_state(|| {
+        ColorScheme::custom(
+            "My scheme",
+            BLUE_SCHEME.color,
+            BLUE_SCHEME.shade,
+            BLUE_SCHEME.border,
+            BLUE_SCHEME.text,
+        )
+    });
+
+    let select = |scheme: &ColorScheme| {
+        let (scheme, on_select, draft) = (scheme.clone(), props.on_select.clone(), draft.clone());
+        Callback::from(move |_: MouseEvent| {
+            let mut edited = scheme.clone();
+            if scheme.is_built_in() {
+                edited.name = Cow::Owned(format!("My {}", scheme.name));
+            }
+            draft.set(edited);
+            on_select.emit(scheme.clone());
+        })
+    };
+
+    // `<input type="color">` has no alpha channel, edits keep the alpha of
+    // the color and the opacity slider sets it for the body
+    let color_input = |label: &'static str, field: SchemeColor| {
+        let color = *field(&mut (*draft).clone());
+        let draft = draft.clone();
+        html! {
+            <label class="color-field">
+                { label }
+                <input
+                    type="color"
+                    value={color.to_rgb_hex()}
+                    oninput={move |event: InputEvent| {
+                        let input: HtmlInputElement = event.target_unchecked_into();
+                        if let Ok(picked) = Color::parse(&input.value()) {
+                            let mut scheme = (*draft).clone();
+                            *field(&mut scheme) = picked.with_alpha(color.a);
+                            draft.set(scheme);
+                        }
+                    }}
+                />
+            </label>
+        }
+    };
+    let on_name = {
+        let draft = draft.clone();
+        move |event: InputEvent| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            let mut scheme = (*draft).clone();
+            scheme.name = Cow::Owned(input.value());
+            draft.set(scheme);
+        }
+    };
+    let on_opacity = {
+        let draft = draft.clone();
+        move |event: InputEvent| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            if let Ok(alpha) = input.value().parse::<u8>() {
+                let mut scheme = (*draft).clone();
+                scheme.color = scheme.color.with_alpha(alpha);
+                scheme.shade = scheme.shade.with_alpha(alpha);
+                draft.set(scheme);
+            }
+        }
+    };
+
+    let name = draft.name.trim().to_string();
+    let replaces_built_in = find_color_scheme(&name).is_some();
+    let exists = props
+        .custom_schemes
+        .iter()
+        .any(|scheme| scheme.name == name);
+    // shade, border and text in the style of the built-in schemes
+    let on_derive = {
+        let draft = draft.clone();
+        move |_| {
+            draft.set(ColorScheme::derive(draft.name.to_string(), draft.color));
+        }
+    };
+    let on_save = {
+        let (draft, on_save) = (draft.clone(), props.on_save.clone());
+        let name = name.clone();
+        move |_| {
+            let mut scheme = (*draft).clone();
+            scheme.name = Cow::Owned(name.clone());
+            on_save.emit(scheme);
+        }
+    };
+
     html! {
         <div class="color-list">
             <h2>{ "Color Schemes" }</h2>
+            {for check_schemes(&props.custom_schemes).iter().map(|warning| html! {
+                <div class="panel-hint color-warning">{ format!("Warning: {warning}") }</div>
+            })}
             <div class="color-grid">
-                {ALL_COLOR_SCHEMES.iter().map(|scheme| {
-                    let on_click = props.on_select.clone();
-                    html! {
-                        <div
-                            class="color-item"
-                            onclick={move |_| on_click.emit(*scheme)}
-                            style="cursor: pointer;"
+                {ALL_COLOR_SCHEMES.iter().map(|scheme| scheme_item(scheme, select(scheme), Html::default())).collect::<Html>()}
+                {props.custom_schemes.iter().map(|scheme| {
+                    let (on_delete, deleted) = (props.on_delete.clone(), scheme.clone());
+                    let delete = html! {
+                        <button
+                            class="color-delete"
+                            title="Delete this scheme"
+                            onclick={move |event: MouseEvent| {
+                                event.stop_propagation();
+                                on_delete.emit(deleted.clone());
+                            }}
                         >
-                            <div class="color-name">{ scheme.name }</div>
-                            <div class="color-description-row">
-                                <div class="color-description"> {"Background"} </div>
-                                <div class="color-description"> {"Border"} </div>
-                            </div>
-                            <div class="color-preview">
-                                <div class="color-sample" style={format!(
-                                    "background: {}; border: 1px solid {}; color: {};",
-                                    scheme.color, scheme.border, scheme.text
-                                )}>{ "Abc" }</div>
-                                <div class="color-sample-shade" style={format!(
-                                    "background: {}; border: 1px solid {};",
-                                    scheme.shade, scheme.border
-                                )}></div>
-                            </div>
-                        </div>
-                    }
+                            { "×" }
+                        </button>
+                    };
+                    scheme_item(scheme, select(scheme), delete)
                 }).collect::<Html>()}
             </div>
+            <div class="color-editor">
+                <h3>{ "Custom scheme" }</h3>
+                <label class="color-field">
+                    { "Name" }
+                    <input type="text" value={draft.name.to_string()} oninput={on_name} />
+                </label>
+                <div class="color-fields">
+                    {color_input("Color", |scheme| &mut scheme.color)}
+                    {color_input("Shade", |scheme| &mut scheme.shade)}
+                    {color_input("Border", |scheme| &mut scheme.border)}
+                    {color_input("Text", |scheme| &mut scheme.text)}
+                </div>
+                <label class="color-field">
+                    { format!("Opacity {}%", (draft.color.opacity() * 100.0).round()) }
+                    <input
+                        type="range"
+                        min="0"
+                        max="255"
+                        value={draft.color.a.to_string()}
+                        oninput={on_opacity}
+                    />
+                </label>
+                <button onclick={on_derive}>{ "Derive shade, border and text from the color" }</button>
+                <div class="color-preview">
+                    <div class="color-sample" style={format!(
+                        "background: {}; border: 1px solid {}; color: {};",
+                        draft.color.to_css(), draft.border.to_css(), draft.text.to_css()
+                    )}>{ "Abc" }</div>
+                    <div class="color-sample-shade" style={format!(
+                        "background: {}; border: 1px solid {};",
+                        draft.shade.to_css(), draft.border.to_css()
+                    )}></div>
+                </div>
+                {contrast_badge(&draft)}
+                if text_contrast(&draft) < MIN_TEXT_CONTRAST {
+                    <div class="panel-hint">
+                        { format!(
+                            "The text is hard to read on this color, aim for a contrast of at least {MIN_TEXT_CONTRAST}:1"
+                        ) }
+                    </div>
+                }
+                if replaces_built_in {
+                    <div class="panel-hint">{ "Built-in schemes keep their name, choose another one" }</div>
+                }
+                <button onclick={on_save} disabled={name.is_empty() || replaces_built_in}>
+                    { if exists { "Update scheme" } else { "Save scheme" } }
+                </button>
+            </div>
         </div>
     }
 }
@@ -1,7 +1,9 @@
 pub mod brick;
-pub mod brick_type_view;
+pub mod brick_palette;
 pub mod color_view;
+pub mod script_editor;
 
 pub use brick::Brick;
-pub use brick_type_view::BrickTypeView;
+pub use brick_palette::BrickPalette;
 pub use color_view::ColorView;
+pub use script_editor::ScriptEditor;
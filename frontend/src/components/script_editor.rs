@@ -0,0 +1,142 @@
+use crate::drag::{DragSource, dropped_source, start_drag};
+use shared::brick::h1_base::WIDTH;
+use shared::script::{BrickPosition, Script};
+use yew::{
+    Callback, DragEvent, Html, MouseEvent, Properties, classes, function_component, html, use_state,
+};
+
+// height of the strips between bricks that accept drops
+const DROP_ZONE_HEIGHT: f32 = 12.0;
+// room below the script for the drop zone after the last brick
+const CANVAS_PADDING: f32 = 40.0;
+const MIN_CANVAS_HEIGHT: f32 = 120.0;
+
+#[derive(Properties, PartialEq)]
+pub struct ScriptEditorProps {
+    pub script: Script,
+    #[prop_or_default]
+    pub selected: Option<BrickPosition>,
+    // a brick was dropped before the brick at the position
+    pub on_drop: Callback<(DragSource, BrickPosition)>,
+    pub on_delete: Callback<BrickPosition>,
+    pub on_select: Callback<Option<BrickPosition>>,
+}
+
+#[function_component(ScriptEditor)]
+pub fn script_editor(props: &ScriptEditorProps) -> Html {
+    let drop_target = use_state(|| None::<BrickPosition>);
+    let layout = props.script.layout();
+    let (width, height) = layout.size();
+    let canvas_height = (height + CANVAS_PADDING).max(MIN_CANVAS_HEIGHT);
+
+    let bricks = layout.bricks.iter().enumerate().map(|(index, placed)| {
+        let (brick_width, brick_height) = placed.brick.size();
+        let source = DragSource::Script(placed.position.clone());
+        let position = placed.position.clone();
+        let on_select = props.on_select.clone();
+        let selected = props.selected.as_ref() == Some(&placed.position);
+
+        html! {
+            <div
+                class={classes!("script-brick", selected.then_some("selected"))}
+                style={format!(
+                    "left: {}px; top: {}px; width: {brick_width}px; height: {brick_height}px;",
+                    placed.x, placed.y
+                )}
+                draggable="true"
+                ondragstart={move |event: DragEvent| start_drag(&event, &source)}
+                onclick={move |event: MouseEvent| {
+                    event.stop_propagation();
+                    on_select.emit(Some(position.clone()));
+                }}
+            >
+                // scoped like in Script::render, inline styles and ids would
+                // otherwise apply to every brick of the page
+                {placed.brick.render(&placed.color_scheme).scoped(&format!("brick{index}")).to_html()}
+            </div>
+        }
+    });
+
+    let drop_zones = layout.drop_points.iter().map(|point| {
+        let active = drop_target.as_ref() == Some(&point.position);
+        // the zone after the script fills the free canvas below it
+        let is_end =
+            point.position.stack.is_empty() && point.position.index == props.script.bricks.len();
+        let zone_height = if is_end {
+            canvas_height - point.y + DROP_ZONE_HEIGHT / 2.0
+        } else {
+            DROP_ZONE_HEIGHT
+        };
+        let (enter_target, enter_position) = (drop_target.clone(), point.position.clone());
+        let leave_target = drop_target.clone();
+        let (drop_target, position, on_drop) = (
+            drop_target.clone(),
+            point.position.clone(),
+            props.on_drop.clone(),
+        );
+
+        html! {
+            <div
+                class={classes!("drop-zone", active.then_some("active"))}
+                style={format!(
+                    "left: {}px; top: {}px; width: {}px; height: {zone_height}px;",
+                    point.x,
+                    point.y - DROP_ZONE_HEIGHT / 2.0,
+                    point.width.max(WIDTH)
+                )}
+                ondragover={move |event: DragEvent| {
+                    event.prevent_default();
+                    if enter_target.as_ref() != Some(&enter_position) {
+                        enter_target.set(Some(enter_position.clone()));
+                    }
+                }}
+                ondragleave={move |_| leave_target.set(None)}
+                ondrop={move |event: DragEvent| {
+                    event.prevent_default();
+                    drop_target.set(None);
+                    if let Some(source) = dropped_source(&event) {
+                        on_drop.emit((source, position.clone()));
+                    }
+                }}
+            />
+        }
+    });
+
+    let on_delete = props.on_delete.clone();
+    let on_deselect = props.on_select.clone();
+
+    html! {
+        <div class="script-editor">
+            <h2>{ "Script" }</h2>
+            <div class="script-canvas-container" onclick={move |_| on_deselect.emit(None)}>
+                <div
+                    class="script-canvas"
+                    style={format!(
+                        "width: {}px; height: {canvas_height}px;",
+                        width.max(WIDTH)
+                    )}
+                >
+                    if props.script.is_empty() {
+                        <div class="script-placeholder">
+                            { "Drag bricks from the brick types or the preview here" }
+                        </div>
+                    }
+                    { for bricks }
+                    { for drop_zones }
+                </div>
+            </div>
+            <div
+                class="script-trash"
+                ondragover={|event: DragEvent| event.prevent_default()}
+                ondrop={move |event: DragEvent| {
+                    event.prevent_default();
+                    if let Some(DragSource::Script(position)) = dropped_source(&event) {
+                        on_delete.emit(position);
+                    }
+                }}
+            >
+                { "Drop a brick here to delete it" }
+            </div>
+        </div>
+    }
+}
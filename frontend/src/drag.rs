@@ -0,0 +1,60 @@
+use shared::catalog::find_catalog_brick;
+use shared::script::BrickPosition;
+use shared::types::BrickType;
+use yew::DragEvent;
+
+// Drag and drop between the palette, the preview and the script editor. The
+// source travels in the DataTransfer of the event, which Firefox requires
+// anyway for a drag to start.
+
+const FORMAT: &str = "text/plain";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DragSource {
+    // a new brick of the type, dragged from the palette
+    Palette(BrickType),
+    // a new brick from the catalog, by its id
+    Catalog(String),
+    // a copy of the brick shown in the preview
+    Preview,
+    // a brick of the script, moved together with its nested bricks
+    Script(BrickPosition),
+}
+
+impl DragSource {
+    fn encode(&self) -> String {
+        match self {
+            Self::Palette(brick_type) => format!("palette:{}", brick_type.id()),
+            Self::Catalog(id) => format!("catalog:{id}"),
+            Self::Preview => "preview".to_string(),
+            Self::Script(position) => format!(
+                "script:{}",
+                serde_json::to_string(position).unwrap_or_default()
+            ),
+        }
+    }
+
+    fn decode(data: &str) -> Option<Self> {
+        match data.split_once(':') {
+            Some(("palette", id)) => BrickType::from_id(id).map(Self::Palette),
+            Some(("catalog", id)) => {
+                find_catalog_brick(id).map(|brick| Self::Catalog(brick.id.clone()))
+            }
+            Some(("script", position)) => serde_json::from_str(position).ok().map(Self::Script),
+            _ => (data == "preview").then_some(Self::Preview),
+        }
+    }
+}
+
+pub fn start_drag(event: &DragEvent, source: &DragSource) {
+    if let Some(transfer) = event.data_transfer() {
+        // a failed set_data only means the drop will be ignored
+        let _ = transfer.set_data(FORMAT, &source.encode());
+        transfer.set_effect_allowed("copyMove");
+    }
+}
+
+pub fn dropped_source(event: &DragEvent) -> Option<DragSource> {
+    let data = event.data_transfer()?.get_data(FORMAT).ok()?;
+    DragSource::decode(&data)
+}
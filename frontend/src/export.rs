@@ -0,0 +1,105 @@
+use shared::raster::PngOptions;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::closure::Closure;
+use web_sys::{
+    Blob, BlobPropertyBag, CanvasRenderingContext2d, HtmlAnchorElement, HtmlCanvasElement,
+    HtmlImageElement, Url,
+};
+
+// how long object URLs of downloads stay valid
+const REVOKE_DELAY_MS: i32 = 10_000;
+
+// Rasterizes an SVG of `size` user units, a brick or a whole script, through
+// a canvas and offers the result as a download. The pixel size is computed by
+// the shared crate, so it matches the PNGs the backend renders for the same
+// options.
+pub fn download_png(svg: &str, size: (f32, f32), options: &PngOptions, file_name: &str) {
+    let (width, height) = options.pixel_size(size);
+    let source = format!(
+        "data:image/svg+xml;charset=utf-8,{}",
+        js_sys::encode_uri_component(svg)
+    );
+    let file_name = file_name.to_string();
+
+    let Ok(image) = HtmlImageElement::new() else {
+        return;
+    };
+    let loaded = image.clone();
+    let on_load = Closure::once_into_js(move || {
+        if let Err(error) = draw_and_save(&loaded, width, height, &file_name) {
+            web_sys::console::error_1(&error);
+        }
+    });
+    image.set_onload(Some(on_load.unchecked_ref()));
+    image.set_src(&source);
+}
+
+fn draw_and_save(
+    image: &HtmlImageElement,
+    width: u32,
+    height: u32,
+    file_name: &str,
+) -> Result<(), wasm_bindgen::JsValue> {
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or("no document")?;
+
+    let canvas: HtmlCanvasElement = document.create_element("canvas")?.dyn_into()?;
+    canvas.set_width(width);
+    canvas.set_height(height);
+    let context: CanvasRenderingContext2d = canvas
+        .get_context("2d")?
+        .ok_or("no 2d context")?
+        .dyn_into()?;
+    context.draw_image_with_html_image_element_and_dw_and_dh(
+        image,
+        0.0,
+        0.0,
+        f64::from(width),
+        f64::from(height),
+    )?;
+
+    click_download(&canvas.to_data_url_with_type("image/png")?, file_name)
+}
+
+// Offers bytes built in the app, e.g. a Catrobat project, as a download.
+pub fn download_bytes(data: &[u8], content_type: &str, file_name: &str) {
+    if let Err(error) = save_bytes(data, content_type, file_name) {
+        web_sys::console::error_1(&error);
+    }
+}
+
+fn save_bytes(
+    data: &[u8],
+    content_type: &str,
+    file_name: &str,
+) -> Result<(), wasm_bindgen::JsValue> {
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(data));
+    let options = BlobPropertyBag::new();
+    options.set_type(content_type);
+    let blob = Blob::new_with_u8_array_sequence_and_options(&parts, &options)?;
+    let url = Url::create_object_url_with_blob(&blob)?;
+    click_download(&url, file_name)?;
+    // revoked later, Firefox cancels downloads whose URL is revoked right away
+    let revoke = Closure::once_into_js(move || {
+        let _ = Url::revoke_object_url(&url);
+    });
+    web_sys::window()
+        .ok_or("no window")?
+        .set_timeout_with_callback_and_timeout_and_arguments_0(
+            revoke.unchecked_ref(),
+            REVOKE_DELAY_MS,
+        )?;
+    Ok(())
+}
+
+fn click_download(href: &str, file_name: &str) -> Result<(), wasm_bindgen::JsValue> {
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or("no document")?;
+    let link: HtmlAnchorElement = document.create_element("a")?.dyn_into()?;
+    link.set_href(href);
+    link.set_download(file_name);
+    link.click();
+    Ok(())
+}
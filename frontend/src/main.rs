@@ -1,57 +1,105 @@
-use shared::color::{BLUE_SCHEME, ColorScheme};
+use shared::catalog::find_catalog_brick;
+use shared::category::{ALL_BRICK_CATEGORIES, BrickCategory};
+use shared::catrobat::{ExportWarning, export_catrobat};
+use shared::color::ColorScheme;
+use shared::document::BrickSpec;
+use shared::editor::{Command, EditorState, History};
+use shared::raster::PngOptions;
+use shared::script::{BrickPosition, ScriptBrick};
 use shared::types::BrickType;
+use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
 use yew::html::Scope;
-use yew::{Component, Context, Html, html};
+use yew::{Component, Context, DragEvent, Event, Html, InputEvent, TargetCast, html};
 
 mod components;
-use components::{Brick, BrickTypeView, ColorView};
+mod drag;
+mod export;
+mod share;
+mod shortcuts;
+mod storage;
+use components::{Brick, BrickPalette, ColorView, ScriptEditor};
+use drag::{DragSource, start_drag};
+use shortcuts::Shortcuts;
+
+// three pixels per SVG unit, sharp enough for slides
+const DEFAULT_PNG_WIDTH: u32 = 1044;
+// name of the exported Catrobat project and of its object
+const CATROBAT_NAME: &str = "BrickCreator";
 
 pub enum Message {
+    CopyShareLink,
+    DeleteBrick(BrickPosition),
+    DeleteColorScheme(ColorScheme),
+    DownloadCatrobat,
+    DownloadPng,
+    DownloadScriptPng,
+    DropBrick(DragSource, BrickPosition),
+    Redo,
     Reset,
+    SaveColorScheme(ColorScheme),
+    SelectBrick(Option<BrickPosition>),
+    SelectCatalogBrick(String),
     ToggleSidebar,
+    UpdateCategory(BrickCategory),
     UpdateColorScheme(ColorScheme),
     UpdateContent(String),
+    UpdateHeight(Option<f32>),
+    UpdatePngWidth(u32),
     UpdateType(BrickType),
+    UpdateWidth(Option<f32>),
+    Undo,
 }
 
 pub struct App {
     sidebar_collapsed: bool,
-    color_scheme: ColorScheme,
-    brick_type: BrickType,
+    // the brick, the script and the selection, changed through commands
+    history: History,
+    png_width: u32,
+    // user defined color schemes, kept apart from the undo history
+    color_schemes: Vec<ColorScheme>,
+    // the last copied share link and the warnings of the last Catrobat
+    // export, until the state changes
+    share_link: Option<String>,
+    export_warnings: Vec<ExportWarning>,
+    _shortcuts: Option<Shortcuts>,
 }
 
 impl Component for App {
     type Message = Message;
     type Properties = ();
 
-    fn create(_ctx: &Context<Self>) -> Self {
+    fn create(ctx: &Context<Self>) -> Self {
+        let mut history = History::new(storage::restore().unwrap_or_default());
+        // a share link replaces the autosaved session, undo brings it back
+        if let Some(shared) = share::take_from_location()
+            && history.apply(Command::Load(shared))
+        {
+            storage::save(history.state());
+        }
+
         Self {
             sidebar_collapsed: false,
-            color_scheme: BLUE_SCHEME,
-            brick_type: BrickType::H0Collapsed,
+            history,
+            png_width: DEFAULT_PNG_WIDTH,
+            color_schemes: storage::restore_color_schemes(),
+            share_link: None,
+            export_warnings: Vec::new(),
+            _shortcuts: Shortcuts::listen(
+                ctx.link().callback(|()| Message::Undo),
+                ctx.link().callback(|()| Message::Redo),
+            ),
         }
     }
 
     fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
-        match msg {
-            Message::Reset => true,
-            Message::ToggleSidebar => {
-                self.sidebar_collapsed = !self.sidebar_collapsed;
-                true
-            }
-            Message::UpdateColorScheme(scheme) => {
-                self.color_scheme = scheme;
-                true
-            }
-            Message::UpdateContent(_content) => {
-                // Handle content update
-                true
-            }
-            Message::UpdateType(brick_type) => {
-                self.brick_type = brick_type;
-                true
-            }
+        let exports = matches!(msg, Message::CopyShareLink | Message::DownloadCatrobat);
+        let changed = self.handle(msg);
+        if changed && !exports {
+            storage::save(self.state());
+            self.share_link = None;
+            self.export_warnings.clear();
         }
+        changed
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
@@ -60,6 +108,7 @@ impl Component for App {
         } else {
             "sidebar"
         };
+        let state = self.state();
 
         html! {
             <div class="app-container">
@@ -74,13 +123,37 @@ impl Component for App {
                     {self.view_panel(ctx.link())}
                 </div>
                 <div class="main-content">
-                    <Brick color_scheme={self.color_scheme} brick_type={self.brick_type} />
-                    <BrickTypeView
-                        selected={self.brick_type}
-                        on_select={ctx.link().callback(|brick_type| Message::UpdateType(brick_type))}
+                    <div
+                        class="brick-preview"
+                        draggable="true"
+                        ondragstart={|event: DragEvent| start_drag(&event, &DragSource::Preview)}
+                    >
+                        <Brick
+                            color_scheme={state.brick.color_scheme.clone()}
+                            brick_type={state.brick.brick_type}
+                            content={state.brick.content.clone()}
+                            width={state.brick.width}
+                            height={state.brick.height}
+                        />
+                    </div>
+                    <ScriptEditor
+                        script={state.script.clone()}
+                        selected={state.selected.clone()}
+                        on_drop={ctx.link().callback(|(source, target)| Message::DropBrick(source, target))}
+                        on_delete={ctx.link().callback(Message::DeleteBrick)}
+                        on_select={ctx.link().callback(Message::SelectBrick)}
+                    />
+                    <BrickPalette
+                        selected={state.brick.brick_type}
+                        content={state.brick.content.clone()}
+                        on_select_brick={ctx.link().callback(Message::SelectCatalogBrick)}
+                        on_select_type={ctx.link().callback(Message::UpdateType)}
                     />
                     <ColorView
-                        on_select={ctx.link().callback(|scheme| Message::UpdateColorScheme(scheme))}
+                        custom_schemes={self.color_schemes.clone()}
+                        on_select={ctx.link().callback(Message::UpdateColorScheme)}
+                        on_save={ctx.link().callback(Message::SaveColorScheme)}
+                        on_delete={ctx.link().callback(Message::DeleteColorScheme)}
                     />
                 </div>
             </div>
@@ -89,10 +162,263 @@ impl Component for App {
 }
 
 impl App {
+    // handles the message, returns whether the view changed
+    fn handle(&mut self, msg: Message) -> bool {
+        match msg {
+            // built on demand, encoding the state on every render is too slow
+            Message::CopyShareLink => {
+                self.share_link = share::share_link(self.state());
+                if let Some(link) = &self.share_link {
+                    share::copy_to_clipboard(link);
+                }
+                true
+            }
+            Message::DeleteBrick(position) => self.history.apply(Command::RemoveBrick(position)),
+            Message::DeleteColorScheme(scheme) => {
+                // bricks keep the colors of a deleted scheme
+                self.color_schemes
+                    .retain(|custom| custom.name != scheme.name);
+                storage::save_color_schemes(&self.color_schemes);
+                true
+            }
+            Message::DownloadCatrobat => {
+                let export = export_catrobat(&self.state().script, CATROBAT_NAME);
+                match export.to_archive() {
+                    Ok(archive) => {
+                        export::download_bytes(&archive, "application/zip", "script.catrobat");
+                    }
+                    Err(error) => web_sys::console::error_1(&error.to_string().into()),
+                }
+                self.export_warnings = export.warnings;
+                true
+            }
+            Message::DownloadPng => {
+                let spec = &self.state().brick;
+                let brick = spec.to_brick();
+                let file_name = format!("{}.png", spec.brick_type.id());
+                export::download_png(
+                    &brick.to_svg_string(&spec.color_scheme),
+                    brick.size(),
+                    &self.png_options(),
+                    &file_name,
+                );
+                false
+            }
+            Message::DownloadScriptPng => {
+                let script = &self.state().script;
+                export::download_png(
+                    &script.to_svg_string(),
+                    script.size(),
+                    &self.png_options(),
+                    "script.png",
+                );
+                false
+            }
+            Message::DropBrick(source, target) => {
+                let command = match source {
+                    DragSource::Palette(brick_type) => {
+                        let color_scheme = self.state().brick.color_scheme.clone();
+                        let brick = BrickSpec::new(brick_type, color_scheme, "");
+                        Command::InsertBrick(target, ScriptBrick::new(brick))
+                    }
+                    DragSource::Catalog(id) => match find_catalog_brick(&id) {
+                        Some(brick) => {
+                            Command::InsertBrick(target, ScriptBrick::new(brick.to_spec()))
+                        }
+                        None => return false,
+                    },
+                    DragSource::Preview => {
+                        Command::InsertBrick(target, ScriptBrick::new(self.state().brick.clone()))
+                    }
+                    DragSource::Script(from) => Command::MoveBrick { from, to: target },
+                };
+                self.history.apply(command)
+            }
+            Message::Redo => self.history.redo(),
+            Message::Reset => {
+                // nothing to lose when the editor is already in its default state
+                let confirmed = *self.state() == EditorState::default()
+                    || web_sys::window()
+                        .and_then(|window| {
+                            window
+                                .confirm_with_message(
+                                    "Reset the brick and clear the script? This can be undone.",
+                                )
+                                .ok()
+                        })
+                        .unwrap_or(false);
+                confirmed && self.history.apply(Command::Reset)
+            }
+            Message::SaveColorScheme(scheme) => {
+                match self
+                    .color_schemes
+                    .iter_mut()
+                    .find(|custom| custom.name == scheme.name)
+                {
+                    Some(custom) => custom.clone_from(&scheme),
+                    None => self.color_schemes.push(scheme.clone()),
+                }
+                storage::save_color_schemes(&self.color_schemes);
+                self.history.apply(Command::SetColorScheme(scheme));
+                true
+            }
+            Message::SelectBrick(position) => self.history.apply(Command::Select(position)),
+            Message::SelectCatalogBrick(id) => find_catalog_brick(&id)
+                .is_some_and(|brick| self.history.apply(Command::SetBrick(brick.to_spec()))),
+            Message::ToggleSidebar => {
+                self.sidebar_collapsed = !self.sidebar_collapsed;
+                true
+            }
+            Message::Undo => self.history.undo(),
+            Message::UpdateCategory(category) => self.history.apply(Command::SetCategory(category)),
+            Message::UpdateColorScheme(scheme) => {
+                self.history.apply(Command::SetColorScheme(scheme))
+            }
+            Message::UpdateContent(content) => self.history.apply(Command::SetContent(content)),
+            Message::UpdateHeight(height) => self.history.apply(Command::SetHeight(height)),
+            Message::UpdatePngWidth(width) => {
+                self.png_width = width;
+                true
+            }
+            Message::UpdateType(brick_type) => self.history.apply(Command::SetType(brick_type)),
+            Message::UpdateWidth(width) => self.history.apply(Command::SetWidth(width)),
+        }
+    }
+
+    const fn state(&self) -> &EditorState {
+        self.history.state()
+    }
+
+    fn png_options(&self) -> PngOptions {
+        PngOptions {
+            width: Some(self.png_width),
+            dpi: None,
+        }
+    }
+
     fn view_panel(&self, _link: &Scope<Self>) -> Html {
+        // an empty field goes back to the size of the type, which is shown as
+        // the placeholder
+        let parse_size = |event: InputEvent| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            input.value().parse::<f32>().ok().filter(|size| *size > 0.0)
+        };
+        let brick = &self.state().brick;
+        let (width, height) = brick.to_brick().size();
+        let category = BrickCategory::of_color_scheme(&brick.color_scheme);
+        let script = &self.state().script;
+
         html! {
             <div>
                 <button onclick={_link.callback(|_| Message::Reset)}>{ "Reset" }</button>
+                <button
+                    onclick={_link.callback(|_| Message::Undo)}
+                    disabled={!self.history.can_undo()}
+                    title="Ctrl+Z"
+                >
+                    { "Undo" }
+                </button>
+                <button
+                    onclick={_link.callback(|_| Message::Redo)}
+                    disabled={!self.history.can_redo()}
+                    title="Ctrl+Y"
+                >
+                    { "Redo" }
+                </button>
+                <label class="panel-field">
+                    { "Category" }
+                    <select onchange={_link.batch_callback(|event: Event| {
+                        let select: HtmlSelectElement = event.target_unchecked_into();
+                        BrickCategory::from_id(&select.value()).map(Message::UpdateCategory)
+                    })}>
+                        // a scheme that is no category default shows as custom
+                        <option value="" selected={category.is_none()}>{ "Custom colors" }</option>
+                        {for ALL_BRICK_CATEGORIES.iter().map(|option| html! {
+                            <option value={option.id()} selected={category == Some(*option)}>
+                                { option.name() }
+                            </option>
+                        })}
+                    </select>
+                </label>
+                <label class="panel-field">
+                    { "Content" }
+                    <textarea
+                        class="content-editor"
+                        rows="3"
+                        value={brick.content.clone()}
+                        oninput={_link.callback(|event: InputEvent| {
+                            let input: HtmlTextAreaElement = event.target_unchecked_into();
+                            Message::UpdateContent(input.value())
+                        })}
+                    />
+                    <span class="panel-hint">
+                        { "{10} value, [item] dropdown, <var> variable" }
+                    </span>
+                </label>
+                <label class="panel-field">
+                    { "Width" }
+                    <input
+                        type="number"
+                        min="1"
+                        placeholder={width.to_string()}
+                        value={brick.width.map(|size| size.to_string()).unwrap_or_default()}
+                        oninput={_link.callback(move |event| Message::UpdateWidth(parse_size(event)))}
+                    />
+                </label>
+                <label class="panel-field">
+                    { "Height" }
+                    <input
+                        type="number"
+                        min="1"
+                        placeholder={height.to_string()}
+                        value={brick.height.map(|size| size.to_string()).unwrap_or_default()}
+                        oninput={_link.callback(move |event| Message::UpdateHeight(parse_size(event)))}
+                    />
+                </label>
+                <label class="panel-field">
+                    { "PNG width" }
+                    <input
+                        type="number"
+                        min="1"
+                        value={self.png_width.to_string()}
+                        oninput={_link.callback(|event: InputEvent| {
+                            let input: HtmlInputElement = event.target_unchecked_into();
+                            Message::UpdatePngWidth(input.value().parse().unwrap_or(DEFAULT_PNG_WIDTH))
+                        })}
+                    />
+                </label>
+                <button
+                    onclick={_link.callback(|_| Message::DownloadPng)}
+                    disabled={self.png_width == 0}
+                >
+                    { "Download PNG" }
+                </button>
+                <button
+                    onclick={_link.callback(|_| Message::DownloadScriptPng)}
+                    disabled={self.png_width == 0 || self.state().script.is_empty()}
+                >
+                    { "Download script PNG" }
+                </button>
+                <button
+                    onclick={_link.callback(|_| Message::DownloadCatrobat)}
+                    disabled={script.is_empty()}
+                >
+                    { "Download .catrobat" }
+                </button>
+                // what the export changed, e.g. bricks that became notes
+                {for self.export_warnings.iter().map(|warning| html! {
+                    <span class="panel-hint">{ warning.to_string() }</span>
+                })}
+                <label class="panel-field">
+                    { "Share link" }
+                    <input
+                        type="text"
+                        readonly=true
+                        placeholder="Copy to create a link"
+                        value={self.share_link.clone().unwrap_or_default()}
+                    />
+                </label>
+                <button onclick={_link.callback(|_| Message::CopyShareLink)}>{ "Copy share link" }</button>
             </div>
         }
     }
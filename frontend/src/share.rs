@@ -0,0 +1,52 @@
+use shared::editor::EditorState;
+use shared::share::{decode_share, encode_share};
+use wasm_bindgen::JsValue;
+
+// Share links: the editor state in the URL fragment of the current page. The
+// link is built from the current location, so it keeps the path the frontend
+// is served under, e.g. /BrickCreator/.
+
+// The state of a share link the page was opened with. The fragment is removed
+// afterwards, so reloading goes back to the autosaved session.
+pub fn take_from_location() -> Option<EditorState> {
+    let window = web_sys::window()?;
+    let location = window.location();
+    let fragment = location.hash().ok()?;
+    if fragment.len() <= 1 {
+        return None;
+    }
+
+    let state = decode_share(&fragment)
+        .map_err(|error| {
+            let message = format!("could not open the share link: {error}");
+            web_sys::console::warn_1(&message.into());
+        })
+        .ok()?;
+    let url = format!(
+        "{}{}",
+        location.pathname().unwrap_or_default(),
+        location.search().unwrap_or_default()
+    );
+    if let Ok(history) = window.history() {
+        let _ = history.replace_state_with_url(&JsValue::NULL, "", Some(&url));
+    }
+    Some(state)
+}
+
+pub fn share_link(state: &EditorState) -> Option<String> {
+    let location = web_sys::window()?.location();
+    let fragment = encode_share(state).ok()?;
+    Some(format!(
+        "{}{}{}#{fragment}",
+        location.origin().ok()?,
+        location.pathname().ok()?,
+        location.search().ok()?
+    ))
+}
+
+pub fn copy_to_clipboard(text: &str) {
+    if let Some(window) = web_sys::window() {
+        // the promise is not awaited, the copied link is shown in the panel
+        let _ = window.navigator().clipboard().write_text(text);
+    }
+}
@@ -0,0 +1,45 @@
+use wasm_bindgen::JsCast;
+use wasm_bindgen::closure::Closure;
+use web_sys::KeyboardEvent;
+use yew::Callback;
+
+// Window wide undo and redo shortcuts: Ctrl+Z, and Ctrl+Y or Ctrl+Shift+Z,
+// with Cmd instead of Ctrl on macOS. They also apply inside the text fields,
+// so typing is undone together with the other edits. The listener is removed
+// when the value is dropped.
+pub struct Shortcuts {
+    listener: Closure<dyn Fn(KeyboardEvent)>,
+}
+
+impl Shortcuts {
+    pub fn listen(on_undo: Callback<()>, on_redo: Callback<()>) -> Option<Self> {
+        let listener = Closure::<dyn Fn(KeyboardEvent)>::new(move |event: KeyboardEvent| {
+            if !(event.ctrl_key() || event.meta_key()) || event.alt_key() {
+                return;
+            }
+            let callback = match event.key().to_lowercase().as_str() {
+                "z" if event.shift_key() => &on_redo,
+                "z" => &on_undo,
+                "y" => &on_redo,
+                _ => return,
+            };
+            event.prevent_default();
+            callback.emit(());
+        });
+        web_sys::window()?
+            .add_event_listener_with_callback("keydown", listener.as_ref().unchecked_ref())
+            .ok()?;
+        Some(Self { listener })
+    }
+}
+
+impl Drop for Shortcuts {
+    fn drop(&mut self) {
+        if let Some(window) = web_sys::window() {
+            let _ = window.remove_event_listener_with_callback(
+                "keydown",
+                self.listener.as_ref().unchecked_ref(),
+            );
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use shared::color::ColorScheme;
+use shared::document::DocumentError;
+use shared::editor::EditorState;
+use shared::session::{load_color_schemes, load_session, save_session};
+use web_sys::Storage;
+
+// Autosave of the editor session and the custom color schemes in the local
+// storage of the browser.
+
+const SESSION_KEY: &str = "brick-creator.session";
+const COLOR_SCHEMES_KEY: &str = "brick-creator.color-schemes";
+
+fn local_storage() -> Option<Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+// The saved value, if there is one that this build can read. Unreadable
+// values are left in place and overwritten by the next save.
+fn load<T>(key: &str, parse: impl FnOnce(&str) -> Result<T, DocumentError>) -> Option<T> {
+    let text = local_storage()?.get_item(key).ok()??;
+    match parse(&text) {
+        Ok(value) => Some(value),
+        Err(error) => {
+            web_sys::console::warn_1(&error.to_string().into());
+            None
+        }
+    }
+}
+
+fn store(key: &str, what: &str, text: Result<String, DocumentError>) {
+    let Some(storage) = local_storage() else {
+        return;
+    };
+    let result = text.map_err(|error| error.to_string()).and_then(|text| {
+        storage
+            .set_item(key, &text)
+            .map_err(|_| "the local storage is full or disabled".to_string())
+    });
+    if let Err(error) = result {
+        web_sys::console::warn_1(&format!("could not save the {what}: {error}").into());
+    }
+}
+
+pub fn restore() -> Option<EditorState> {
+    load(SESSION_KEY, load_session)
+}
+
+pub fn save(state: &EditorState) {
+    store(SESSION_KEY, "session", save_session(state));
+}
+
+pub fn restore_color_schemes() -> Vec<ColorScheme> {
+    load(COLOR_SCHEMES_KEY, load_color_schemes).unwrap_or_default()
+}
+
+pub fn save_color_schemes(schemes: &[ColorScheme]) {
+    store(
+        COLOR_SCHEMES_KEY,
+        "color schemes",
+        shared::session::save_color_schemes(schemes),
+    );
+}
@@ -1,16 +1,203 @@
-use crate::color::ColorScheme;
-use yew::{Html, html};
+use crate::color::{BLACK_TEXT, Color, ColorScheme};
+use crate::content::{content_to_svg, parse_content};
+use crate::format::num;
+use crate::layout::{CONTENT_LEFT, CONTENT_MARGIN, ContentArea};
+use crate::svg::SvgElement;
+use yew::Html;
+
+// Inner slot of a C-shaped brick. Nested bricks stack from (`x`, `y`), the
+// top border of the first one lying on the border above the slot.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Slot {
+    pub x: f32,
+    pub y: f32,
+    pub height: f32,
+}
 
 pub trait BrickSVG {
-    fn to_svg(&self, color_scheme: &ColorScheme) -> Html;
+    fn render(&self, color_scheme: &ColorScheme) -> SvgElement;
     fn set_content(&mut self, content: &str);
     fn get_content(&self) -> &str;
+    // width and height of the brick in SVG user units
+    fn size(&self) -> (f32, f32);
+    // Resizes the brick. The body stretches while the notch, the tab blocks
+    // and the corners keep their shape; sizes below the minimum are clamped.
+    fn set_size(&mut self, width: f32, height: f32);
 
-    fn content_to_svg(&self) -> Html {
-        html! {
-            <text x="50%" y="50%" dominant-baseline="middle" text-anchor="middle" fill="currentColor" font-size="4">
-                { self.get_content() }
-            </text>
+    fn with_size(mut self, width: f32, height: f32) -> Self
+    where
+        Self: Sized,
+    {
+        self.set_size(width, height);
+        self
+    }
+
+    // inner slots for nested bricks from top to bottom, none for plain bricks
+    fn slots(&self) -> Vec<Slot> {
+        Vec::new()
+    }
+
+    // Sets the stacked height of the bricks nested in each slot, the brick
+    // grows to hold them. Ignored by bricks without slots.
+    fn set_slot_heights(&mut self, _heights: &[f32]) {}
+
+    // region of the brick available for text, right of the tab blocks
+    fn content_area(&self) -> ContentArea {
+        let (width, height) = self.size();
+        ContentArea {
+            x: CONTENT_LEFT,
+            y: CONTENT_MARGIN,
+            width: width - CONTENT_LEFT - CONTENT_MARGIN,
+            height: height - 2.0 * CONTENT_MARGIN,
         }
     }
+
+    fn content_to_svg(&self) -> SvgElement {
+        content_to_svg(&parse_content(self.get_content()), &self.content_area())
+    }
+
+    fn to_svg(&self, color_scheme: &ColorScheme) -> Html {
+        self.render(color_scheme).to_html()
+    }
+
+    fn to_svg_string(&self, color_scheme: &ColorScheme) -> String {
+        self.render(color_scheme).to_string()
+    }
+}
+
+// shared building blocks of the brick shapes
+
+// Control bricks start their body below the curved hat.
+pub const CONTROL_HAT_HEIGHT: f32 = 24.0;
+
+pub fn control_content_area((width, height): (f32, f32)) -> ContentArea {
+    ContentArea {
+        x: CONTENT_LEFT,
+        y: CONTROL_HAT_HEIGHT,
+        width: width - CONTENT_LEFT - CONTENT_MARGIN,
+        height: height - CONTROL_HAT_HEIGHT - CONTENT_MARGIN,
+    }
+}
+
+pub fn svg_root(width: f32, height: f32) -> SvgElement {
+    SvgElement::new("svg")
+        .attr("xmlns", "http://www.w3.org/2000/svg")
+        .attr("viewBox", format!("0 0 {width} {height}"))
+}
+
+pub fn style(color_scheme: &ColorScheme) -> SvgElement {
+    let ColorScheme {
+        color,
+        shade,
+        border,
+        text,
+        ..
+    } = color_scheme;
+    SvgElement::new("style").text(format!(
+        ".background {{ {} }}
+.border {{ {} }}
+.shade {{ {} }}
+.text {{ {} }}
+.value-field {{ fill: #ffffff; {} stroke-width: 0.75; }}
+.value-text {{ {} }}
+.dropdown-field {{ {} {} stroke-width: 0.75; }}
+.variable-field {{ {} {} stroke-width: 0.75; }}",
+        color.to_svg_paint("fill"),
+        border.to_svg_paint("fill"),
+        shade.with_alpha(u8::MAX).to_svg_paint("stop-color"),
+        text.to_svg_paint("fill"),
+        border.to_svg_paint("stroke"),
+        BLACK_TEXT.to_svg_paint("fill"),
+        shade.to_svg_paint("fill"),
+        border.to_svg_paint("stroke"),
+        shade.to_svg_paint_with_opacity("fill", 0.5),
+        text.to_svg_paint("stroke"),
+    ))
+}
+
+pub fn path(class: &str, d: &str) -> SvgElement {
+    SvgElement::new("path").attr("class", class).attr("d", d)
+}
+
+pub fn highlight(d: &str) -> SvgElement {
+    SvgElement::new("path")
+        .attr("fill-opacity", "0.4")
+        .attr("fill", "#fff")
+        .attr("d", d)
+        .attr("stroke-opacity", "0.4")
+}
+
+pub fn tab_column(positions: &[f32]) -> SvgElement {
+    SvgElement::new("g")
+        .attr("viewbox", "0 0 348 69")
+        .children(positions.iter().map(|y| {
+            SvgElement::new("use")
+                .attr("y", y.to_string())
+                .attr("href", "#tabblock")
+        }))
+}
+
+// the alpha of a translucent shade scales the stop opacities of the
+// gradients, the style only sets the shade color so they still fade
+pub fn defs(tab_height: f32, shade: Color) -> SvgElement {
+    let gradient = |id: &str, x1: &str, x2: &str, y2: &str, opacity: f32, end: &str| {
+        SvgElement::new("linearGradient")
+            .attr("id", id)
+            .attr("x1", x1)
+            .attr("x2", x2)
+            .attr("y1", "0%")
+            .attr("y2", y2)
+            .child(
+                SvgElement::new("stop")
+                    .attr("offset", "0%")
+                    .attr("class", "shade")
+                    .attr("stop-opacity", num(opacity * shade.opacity())),
+            )
+            .child(
+                SvgElement::new("stop")
+                    .attr("offset", end)
+                    .attr("class", "shade")
+                    .attr("stop-opacity", "0"),
+            )
+    };
+    let tab = |fill: &str| {
+        SvgElement::new("rect")
+            .attr("width", "25.458")
+            .attr("height", tab_height.to_string())
+            .attr("x", "10.271")
+            .attr("y", "22.5")
+            .attr("fill", fill)
+    };
+
+    SvgElement::new("defs")
+        .child(gradient("grad1", "0%", "0%", "100%", 0.3, "35%"))
+        .child(gradient("grad2", "0%", "100%", "0%", 0.125, "2.5%"))
+        .child(gradient("grad3", "100%", "0%", "0%", 0.125, "2.5%"))
+        .child(
+            SvgElement::new("g").attr("id", "tabblock").child(
+                SvgElement::new("g")
+                    .child(tab("#fff").attr("fill-opacity", "0.025"))
+                    .child(tab("url(#grad1)"))
+                    .child(tab("url(#grad2)"))
+                    .child(tab("url(#grad3)")),
+            ),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brick::geometry::TAB_HEIGHT;
+    use crate::color::TRANSPARENT_WHITE_SCHEME;
+
+    #[test]
+    fn translucent_shades_keep_their_fade() {
+        let defs = defs(TAB_HEIGHT, Color::hex("#ffffff40")).to_string();
+        assert!(defs.contains(r#"stop-opacity="0.075""#), "{defs}");
+        assert!(defs.contains(r#"stop-opacity="0.031""#), "{defs}");
+        assert!(defs.contains(r#"stop-opacity="0""#), "{defs}");
+
+        let style = style(&TRANSPARENT_WHITE_SCHEME).to_string();
+        assert!(style.contains(".shade { stop-color: #ffffff; }"), "{style}");
+    }
 }
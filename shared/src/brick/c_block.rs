@@ -0,0 +1,327 @@
+use crate::brick::base::{BrickSVG, Slot, defs, path, style, svg_root};
+use crate::brick::geometry::{
+    BOTTOM_OFFSET, BrickGeometry, MIN_BASE_WIDTH, NOTCH_X, clamp_length, edge_border,
+    notched_strip, nub,
+};
+use crate::color::ColorScheme;
+use crate::content::{ContentPart, content_to_svg, parse_content};
+use crate::format::num;
+use crate::layout::{CONTENT_LEFT, CONTENT_MARGIN, ContentArea, LINE_HEIGHT, layout_content};
+use crate::svg::SvgElement;
+
+// C-shaped bricks like "If … else … end if" or "Repeat … end of loop". The
+// header arm holds the content, every following arm closes the slot above it
+// and the last arm is the closing end brick. Slots grow with the bricks
+// nested in them.
+//
+//   ┌───────────────┐
+//   │ header        │
+//   │  ┌────────────┘
+//   │  │ slot
+//   │  └────────────┐
+//   │ end arm       │
+//   └───────────────┘
+
+pub const WIDTH: f32 = 348.181;
+// indent of the slots, the wall left of them keeps the tab blocks
+pub const INNER_X: f32 = 40.0;
+// stacked height of the header and middle arms, and of the end arm
+pub const ARM_HEIGHT: f32 = 44.0;
+pub const END_ARM_HEIGHT: f32 = 36.0;
+// height of a slot without nested bricks
+pub const EMPTY_SLOT_HEIGHT: f32 = 24.0;
+// the inner connector needs the same room as the outer one
+pub const MIN_WIDTH: f32 = INNER_X + MIN_BASE_WIDTH;
+
+pub const ELSE_LABEL: &str = "Else";
+pub const END_IF_LABEL: &str = "End if";
+pub const END_LOOP_LABEL: &str = "End of loop";
+
+// one horizontal arm, from its top border to its bottom border
+struct Arm<'a> {
+    top: f32,
+    height: f32,
+    label: Option<&'a str>,
+}
+
+pub struct BrickCBlock {
+    // labels of the arms below the header, one slot above each
+    arms: &'static [&'static str],
+    // endless loops have no nub, nothing runs after them
+    has_bottom_nub: bool,
+    width: f32,
+    slot_heights: Vec<f32>,
+    content: String,
+}
+
+impl BrickCBlock {
+    pub fn new(arms: &'static [&'static str], has_bottom_nub: bool) -> Self {
+        Self {
+            arms,
+            has_bottom_nub,
+            width: WIDTH,
+            slot_heights: Vec::new(),
+            content: String::new(),
+        }
+    }
+
+    pub fn if_then() -> Self {
+        Self::new(&[END_IF_LABEL], true)
+    }
+
+    pub fn if_else() -> Self {
+        Self::new(&[ELSE_LABEL, END_IF_LABEL], true)
+    }
+
+    pub fn repeat() -> Self {
+        Self::new(&[END_LOOP_LABEL], true)
+    }
+
+    pub fn forever() -> Self {
+        Self::new(&[END_LOOP_LABEL], false)
+    }
+
+    fn text_width(&self) -> f32 {
+        self.width - CONTENT_LEFT - CONTENT_MARGIN
+    }
+
+    // the header grows with the lines of its content
+    fn header_height(&self) -> f32 {
+        let lines = layout_content(&parse_content(&self.content), self.text_width())
+            .lines
+            .len();
+        ARM_HEIGHT.max((lines as f32).mul_add(LINE_HEIGHT, 2.0 * CONTENT_MARGIN))
+    }
+
+    fn slot_height(&self, index: usize) -> f32 {
+        self.slot_heights
+            .get(index)
+            .copied()
+            .filter(|height| *height > 0.0)
+            .unwrap_or(EMPTY_SLOT_HEIGHT)
+    }
+
+    fn layout(&self) -> Vec<Arm<'_>> {
+        let mut arms = vec![Arm {
+            top: 0.0,
+            height: self.header_height(),
+            label: None,
+        }];
+        for (index, label) in self.arms.iter().enumerate() {
+            let above = &arms[index];
+            let height = if index + 1 == self.arms.len() {
+                END_ARM_HEIGHT
+            } else {
+                ARM_HEIGHT
+            };
+            arms.push(Arm {
+                top: above.top + above.height + self.slot_height(index),
+                height,
+                label: Some(label),
+            });
+        }
+        arms
+    }
+
+    fn label_area(&self, arm: &Arm) -> ContentArea {
+        ContentArea {
+            x: CONTENT_LEFT,
+            y: arm.top + 2.0,
+            width: self.text_width(),
+            height: arm.height - 2.0,
+        }
+    }
+}
+
+impl BrickSVG for BrickCBlock {
+    fn set_content(&mut self, content: &str) {
+        self.content = content.to_string();
+    }
+
+    fn get_content(&self) -> &str {
+        &self.content
+    }
+
+    fn size(&self) -> (f32, f32) {
+        let height = self.layout().last().map_or(0.0, |arm| arm.top + arm.height);
+        (self.width, height + BOTTOM_OFFSET)
+    }
+
+    // only the width can be set, the height follows the content and slots
+    fn set_size(&mut self, width: f32, _height: f32) {
+        self.width = clamp_length(width, MIN_WIDTH);
+    }
+
+    fn slots(&self) -> Vec<Slot> {
+        self.layout()
+            .windows(2)
+            .map(|arms| {
+                let y = arms[0].top + arms[0].height;
+                Slot {
+                    x: INNER_X,
+                    y,
+                    height: arms[1].top - y,
+                }
+            })
+            .collect()
+    }
+
+    fn set_slot_heights(&mut self, heights: &[f32]) {
+        self.slot_heights = heights.to_vec();
+    }
+
+    fn content_area(&self) -> ContentArea {
+        self.label_area(&self.layout()[0])
+    }
+
+    fn render(&self, color_scheme: &ColorScheme) -> SvgElement {
+        let (width, height) = self.size();
+        let arms = self.layout();
+        let inner_notch = INNER_X + NOTCH_X;
+        let outline = BrickGeometry::base(width, height);
+        let rect = |x0: f32, y0: f32, x1: f32, y1: f32| {
+            path(
+                "background",
+                &format!(
+                    "M{},{}H{}V{}H{}z",
+                    num(x0),
+                    num(y0),
+                    num(x1),
+                    num(y1),
+                    num(x0)
+                ),
+            )
+        };
+
+        let last = &arms[arms.len() - 1];
+        let mut background = SvgElement::new("g")
+            // wall on the left, from the header down to the end arm
+            .child(rect(0.0, 5.0, INNER_X, last.top + 5.0));
+        let mut borders = SvgElement::new("g").child(outline.base_top());
+        let mut content = SvgElement::new("g").child(self.content_to_svg());
+
+        for (index, arm) in arms.iter().enumerate() {
+            let bottom = arm.top + arm.height;
+            background = background.child(rect(0.0, arm.top + 5.0, width, bottom + 2.0));
+            if index > 0 {
+                background = background.child(notched_strip(INNER_X, width, arm.top, inner_notch));
+                borders = borders.child(edge_border(
+                    INNER_X - 2.0,
+                    width,
+                    arm.top,
+                    Some(inner_notch),
+                ));
+            }
+            if index + 1 < arms.len() {
+                // nub and border above the slot, and the inner side of the wall
+                background = background.child(nub(INNER_X, bottom, inner_notch));
+                borders = borders
+                    .child(edge_border(INNER_X - 2.0, width, bottom, Some(inner_notch)))
+                    .child(path(
+                        "border",
+                        &format!(
+                            "M{},{}h2V{}h-2z",
+                            num(INNER_X - 2.0),
+                            num(bottom),
+                            num(arms[index + 1].top + 2.0)
+                        ),
+                    ));
+            } else if self.has_bottom_nub {
+                background = background.child(nub(0.0, bottom, NOTCH_X));
+                borders = borders.child(edge_border(0.0, width, bottom, Some(NOTCH_X)));
+            } else {
+                borders = borders.child(edge_border(0.0, width, bottom, None));
+            }
+            if let Some(label) = arm.label {
+                content = content.child(content_to_svg(
+                    &[ContentPart::Text(label.to_string())],
+                    &self.label_area(arm),
+                ));
+            }
+        }
+
+        svg_root(width, height)
+            .child(style(color_scheme))
+            .child(defs(outline.tab_height, color_scheme.shade))
+            .child(background)
+            .child(borders)
+            .child(outline.tabs())
+            .child(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::ORANGE_SCHEME;
+
+    fn slot(y: f32, height: f32) -> Slot {
+        Slot {
+            x: INNER_X,
+            y,
+            height,
+        }
+    }
+
+    #[test]
+    fn if_else_arms_enclose_two_slots() {
+        let mut brick = BrickCBlock::if_else();
+        assert_eq!(brick.slots(), [slot(44.0, 24.0), slot(112.0, 24.0)]);
+        assert_eq!(brick.size(), (WIDTH, 172.0 + BOTTOM_OFFSET));
+
+        // empty slots keep their height, the arms below move down
+        brick.set_slot_heights(&[100.0, 0.0]);
+        assert_eq!(brick.slots(), [slot(44.0, 100.0), slot(188.0, 24.0)]);
+        assert_eq!(brick.size(), (WIDTH, 248.0 + BOTTOM_OFFSET));
+        brick.set_slot_heights(&[]);
+        assert_eq!(brick.slots(), [slot(44.0, 24.0), slot(112.0, 24.0)]);
+    }
+
+    #[test]
+    fn headers_grow_with_their_content() {
+        let mut brick = BrickCBlock::repeat();
+        brick.set_content("Repeat {10} times");
+        assert_eq!(brick.slots(), [slot(44.0, 24.0)]);
+
+        brick.set_content(&vec!["word"; 40].join(" "));
+        let lines = layout_content(&parse_content(brick.get_content()), brick.text_width())
+            .lines
+            .len();
+        assert!(lines > 2);
+        let header = (lines as f32).mul_add(LINE_HEIGHT, 2.0 * CONTENT_MARGIN);
+        assert_eq!(brick.slots(), [slot(header, 24.0)]);
+        assert_eq!(brick.content_area().height, header - 2.0);
+    }
+
+    #[test]
+    fn forever_has_no_bottom_nub() {
+        let forever = BrickCBlock::forever();
+        let repeat = BrickCBlock::repeat();
+        assert_eq!(forever.slots(), [slot(44.0, 24.0)]);
+        assert_eq!(forever.size(), (WIDTH, 104.0 + BOTTOM_OFFSET));
+        assert_eq!(forever.size(), repeat.size());
+
+        // the nub below the end arm at y 104
+        let bottom_nub = nub(0.0, 104.0, NOTCH_X).to_string();
+        assert!(
+            repeat
+                .render(&ORANGE_SCHEME)
+                .to_string()
+                .contains(&bottom_nub)
+        );
+        assert!(
+            !forever
+                .render(&ORANGE_SCHEME)
+                .to_string()
+                .contains(&bottom_nub)
+        );
+        // the nub into the slot is kept
+        let inner_nub = nub(INNER_X, 44.0, INNER_X + NOTCH_X).to_string();
+        assert!(
+            forever
+                .render(&ORANGE_SCHEME)
+                .to_string()
+                .contains(&inner_nub)
+        );
+    }
+}
@@ -0,0 +1,358 @@
+use crate::brick::base::{defs, highlight, path, style, svg_root, tab_column};
+use crate::color::ColorScheme;
+use crate::format::num;
+use crate::svg::SvgElement;
+
+// Parametric outline of a brick. The connector notch, the top (flat with a
+// notch, or the curved hat of control bricks), the tab block column and the
+// bottom nub are derived from the parameters, so bricks of any size share
+// one drawing.
+
+// default x of the connector notch, matching the Catrobat bricks
+pub const NOTCH_X: f32 = 11.0;
+// distance from the top of the bottom border to the bottom of the image, a
+// brick stacked below starts this far above the bottom of the one above
+pub const BOTTOM_OFFSET: f32 = 6.3;
+// y of the first tab block and the space kept below the last one
+const TAB_COLUMN_TOP: f32 = 25.5;
+const TAB_COLUMN_MARGIN: f32 = 12.0;
+const TAB_GAP: f32 = 2.864;
+pub const TAB_HEIGHT: f32 = 7.636;
+// y of the tab block rectangle inside the #tabblock definition
+const TAB_BLOCK_Y: f32 = 22.5;
+
+// smallest sizes that still fit the notch, the nub and the top shape
+pub const MIN_BASE_WIDTH: f32 = 60.0;
+pub const MIN_BASE_HEIGHT: f32 = 16.0;
+pub const MIN_CONTROL_WIDTH: f32 = 230.0;
+pub const MIN_CONTROL_HEIGHT: f32 = 32.0;
+// largest width and height, far beyond any real brick
+pub const MAX_BRICK_SIZE: f32 = 10_000.0;
+// tab blocks drawn at most, more than a brick of the largest size holds
+const MAX_TAB_COUNT: f32 = 1024.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TopShape {
+    // flat top with a connector notch, stacks below another brick
+    Base,
+    // curved hat of the control bricks that start a script
+    Control,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BrickGeometry {
+    pub width: f32,
+    pub height: f32,
+    pub top: TopShape,
+    pub notch_x: f32,
+    pub tab_height: f32,
+}
+
+pub fn clamp_size(top: TopShape, width: f32, height: f32) -> (f32, f32) {
+    let (min_width, min_height) = match top {
+        TopShape::Base => (MIN_BASE_WIDTH, MIN_BASE_HEIGHT),
+        TopShape::Control => (MIN_CONTROL_WIDTH, MIN_CONTROL_HEIGHT),
+    };
+    (
+        clamp_length(width, min_width),
+        clamp_length(height, min_height),
+    )
+}
+
+// Limits a length to `min` up to MAX_BRICK_SIZE. Unlike `f32::clamp`, NaN
+// becomes `min`.
+pub fn clamp_length(length: f32, min: f32) -> f32 {
+    length.max(min).min(MAX_BRICK_SIZE)
+}
+
+// Horizontal border line, two units thick, from `x0` to `x1` with its top at
+// `y`. With a notch the line dips down around the connector at `notch_x`.
+pub fn edge_border(x0: f32, x1: f32, y: f32, notch_x: Option<f32>) -> SvgElement {
+    let (x0, x1, top) = (num(x0), num(x1), num(y));
+    let d = match notch_x {
+        Some(n) => format!(
+            "M{x1},{top}H{}l-1.749,4.126h-16.858l-1.752,-4.126H{x0}v2H{}l1.748,4.115h19.502l1.752,-4.123H{x1}z",
+            num(n + 21.68),
+            num(n - 0.002)
+        ),
+        None => format!("M{x1},{top}H{x0}v2H{x1}z"),
+    };
+    path("border", &d)
+}
+
+// Background right below a top border, leaving the notch at `notch_x` open
+// for the nub of the brick above.
+pub fn notched_strip(x0: f32, x1: f32, y: f32, notch_x: f32) -> SvgElement {
+    path(
+        "background",
+        &format!(
+            "M{},{}H{}l1.75,4.125h19.5l1.75,-4.125H{}v4.125H{}z",
+            num(x0),
+            num(y + 2.0),
+            num(notch_x),
+            num(x1),
+            num(x0)
+        ),
+    )
+}
+
+// Connector nub hanging below the border at `y`, filled from `x0`.
+pub fn nub(x0: f32, y: f32, notch_x: f32) -> SvgElement {
+    path(
+        "background",
+        &format!(
+            "M{},{}H{}l1.75,4.125h16.857l1.75,-4.125H{}z",
+            num(x0),
+            num(y),
+            num(notch_x + 1.318),
+            num(notch_x + 34.042)
+        ),
+    )
+}
+
+impl BrickGeometry {
+    pub const fn base(width: f32, height: f32) -> Self {
+        Self {
+            width,
+            height,
+            top: TopShape::Base,
+            notch_x: NOTCH_X,
+            tab_height: TAB_HEIGHT,
+        }
+    }
+
+    pub const fn control(width: f32, height: f32) -> Self {
+        Self {
+            top: TopShape::Control,
+            ..Self::base(width, height)
+        }
+    }
+
+    #[must_use]
+    pub const fn with_notch_x(mut self, notch_x: f32) -> Self {
+        self.notch_x = notch_x;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_tab_height(mut self, tab_height: f32) -> Self {
+        self.tab_height = tab_height;
+        self
+    }
+
+    // y of the top edge of the bottom border
+    pub fn bottom(&self) -> f32 {
+        self.height - BOTTOM_OFFSET
+    }
+
+    pub const fn body_top(&self) -> f32 {
+        match self.top {
+            TopShape::Base => 5.0,
+            TopShape::Control => 20.0,
+        }
+    }
+
+    // y of every tab block, stacked from the top of the column one tab and
+    // gap apart, as many as fit into the free part of the body. The H1
+    // bricks get the 10.5 spacing of the original Catrobat drawing.
+    pub fn tab_positions(&self) -> Vec<f32> {
+        let available = self.bottom() - TAB_COLUMN_MARGIN - TAB_COLUMN_TOP;
+        let pitch = self.tab_height + TAB_GAP;
+        let count = ((available + TAB_GAP) / pitch)
+            .floor()
+            .clamp(0.0, MAX_TAB_COUNT) as usize;
+        (0..count)
+            .map(|index| (index as f32).mul_add(pitch, TAB_COLUMN_TOP))
+            .collect()
+    }
+
+    fn body(&self) -> SvgElement {
+        path(
+            "background",
+            &format!(
+                "M0,{}H{}V{}H0z",
+                num(self.body_top()),
+                num(self.width),
+                num(self.bottom() + 2.0)
+            ),
+        )
+    }
+
+    pub fn base_top(&self) -> SvgElement {
+        let (w, n) = (num(self.width), self.notch_x);
+        SvgElement::new("g")
+            .child(notched_strip(0.0, self.width, 0.0, n))
+            .child(edge_border(0.0, self.width, 0.0, Some(n)))
+            .child(highlight(&format!(
+                "M{w},2.063H{}l-1.759,4.125h-19.484l-1.74,-4.125H0v2h{}l1.753,4.127h22.115l1.753,-4.127H{w}z",
+                num(n + 22.983),
+                num(n - 1.335)
+            )))
+    }
+
+    fn control_top(&self) -> SvgElement {
+        let w = num(self.width);
+        SvgElement::new("g")
+            .child(path(
+                "border",
+                &format!(
+                    "M214,19c-12.279,-0.277 -17.75,1 -35.25,-3.25c-20.8,-5 -66,-15.5 -110.784,-15.748S0,9.35 0,9.35v14.73L{w},24.222V19z"
+                ),
+            ))
+            .child(path(
+                "background",
+                &format!(
+                    "M212.75,20c-12.75,0 -17.75,1 -35.25,-3.25c-20.8,-5.051 -66,-15.5 -109.75,-15.5S0,10.562 0,11V24.778H{w}V20z"
+                ),
+            ))
+            .child(highlight(&format!(
+                "M212.75,20c-12.75,0 -17.75,1 -35.25,-3.25c-20.8,-5.052 -66,-15.5 -110.441,-15.533S-0.002,11 0,11v3.004s22.6,-10 67.039,-10.002S156.7,14.448 177.5,19.5c17.5,4.25 22.5,3.25 40.25,3.25H{w}v-2.749z"
+            )))
+    }
+
+    fn bottom_edge(&self) -> SvgElement {
+        let (n, b) = (self.notch_x, self.bottom());
+        SvgElement::new("g")
+            .child(nub(0.0, b, n))
+            .child(edge_border(0.0, self.width, b, Some(n)))
+    }
+
+    // tab blocks down the left side, as `use` references to #tabblock
+    pub fn tabs(&self) -> SvgElement {
+        let tabs: Vec<f32> = self
+            .tab_positions()
+            .into_iter()
+            .map(|y| y - TAB_BLOCK_Y)
+            .collect();
+        tab_column(&tabs)
+    }
+
+    // the complete outline, without content
+    pub fn render(&self, color_scheme: &ColorScheme) -> SvgElement {
+        let top = match self.top {
+            TopShape::Base => self.base_top(),
+            TopShape::Control => self.control_top(),
+        };
+
+        svg_root(self.width, self.height)
+            .child(style(color_scheme))
+            .child(defs(self.tab_height, color_scheme.shade))
+            // middle scalable background
+            .child(self.body())
+            .child(top)
+            // middle path for bars on the left
+            .child(self.tabs())
+            .child(self.bottom_edge())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brick::create_brick;
+    use crate::color::ORANGE_SCHEME;
+    use crate::types::{ALL_BRICK_TYPES, BrickType};
+
+    fn assert_positions(geometry: BrickGeometry, expected: &[f32]) {
+        let positions = geometry.tab_positions();
+        assert_eq!(positions.len(), expected.len(), "{positions:?}");
+        for (position, expected) in positions.iter().zip(expected) {
+            assert!((position - expected).abs() < 1e-3, "{positions:?}");
+        }
+    }
+
+    #[test]
+    fn sizes_are_clamped() {
+        assert_eq!(
+            clamp_size(TopShape::Base, 1.0, -5.0),
+            (MIN_BASE_WIDTH, MIN_BASE_HEIGHT)
+        );
+        assert_eq!(
+            clamp_size(TopShape::Control, 100.0, 10.0),
+            (MIN_CONTROL_WIDTH, MIN_CONTROL_HEIGHT)
+        );
+        assert_eq!(clamp_size(TopShape::Base, 123.5, 45.25), (123.5, 45.25));
+        assert_eq!(
+            clamp_size(TopShape::Base, 1e6, MAX_BRICK_SIZE + 1.0),
+            (MAX_BRICK_SIZE, MAX_BRICK_SIZE)
+        );
+        assert_eq!(
+            clamp_size(TopShape::Base, f32::INFINITY, f32::NEG_INFINITY),
+            (MAX_BRICK_SIZE, MIN_BASE_HEIGHT)
+        );
+        assert_eq!(
+            clamp_size(TopShape::Control, f32::NAN, f32::NAN),
+            (MIN_CONTROL_WIDTH, MIN_CONTROL_HEIGHT)
+        );
+
+        for brick_type in ALL_BRICK_TYPES {
+            for (width, height) in [
+                (f32::NAN, f32::NAN),
+                (f32::INFINITY, f32::INFINITY),
+                (-1.0, 0.0),
+            ] {
+                let mut brick = create_brick(*brick_type);
+                brick.set_size(width, height);
+                let (width, height) = brick.size();
+                assert!(
+                    (MIN_BASE_WIDTH..=MAX_BRICK_SIZE).contains(&width)
+                        && (MIN_BASE_HEIGHT..=MAX_BRICK_SIZE).contains(&height),
+                    "{brick_type:?}: {width} x {height}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn tabs_keep_their_spacing() {
+        // the tab blocks of the original H1 drawing
+        assert_positions(BrickGeometry::base(348.181, 72.95), &[25.5, 36.0, 46.5]);
+        assert_positions(BrickGeometry::control(348.181, 72.95), &[25.5, 36.0, 46.5]);
+        assert_positions(
+            BrickGeometry::base(348.181, 94.748).with_tab_height(6.836),
+            &[25.5, 35.2, 44.9, 54.6, 64.3],
+        );
+        assert_positions(
+            BrickGeometry::base(348.181, 94.748).with_tab_height(4.836),
+            &[25.5, 33.2, 40.9, 48.6, 56.3, 64.0],
+        );
+        // collapsed bricks have no room for tabs
+        assert_positions(BrickGeometry::base(350.0, 16.0), &[]);
+
+        let tall = BrickGeometry::base(348.181, 500.0);
+        let positions = tall.tab_positions();
+        let last = positions.last().unwrap();
+        assert!(last + TAB_HEIGHT <= tall.bottom() - TAB_COLUMN_MARGIN);
+        assert!(last + 2.0 * (TAB_HEIGHT + TAB_GAP) > tall.bottom() - TAB_COLUMN_MARGIN);
+        assert_eq!(
+            BrickGeometry::base(100.0, 1e9).tab_positions().len(),
+            MAX_TAB_COUNT as usize
+        );
+    }
+
+    #[test]
+    fn outlines_have_the_size_of_their_brick() {
+        let sizes = [
+            (BrickType::H0Collapsed, (350.0, 16.0)),
+            (BrickType::H1Base, (348.181, 72.95)),
+            (BrickType::H1Control, (348.181, 72.95)),
+            (BrickType::H2Base, (348.181, 94.748)),
+            (BrickType::H2Control, (348.181, 94.748)),
+            (BrickType::H3Base, (348.181, 94.748)),
+            (BrickType::If, (348.181, 110.3)),
+            (BrickType::IfElse, (348.181, 178.3)),
+            (BrickType::Repeat, (348.181, 110.3)),
+            (BrickType::Forever, (348.181, 110.3)),
+        ];
+        for (brick_type, size) in sizes {
+            let brick = create_brick(brick_type);
+            assert_eq!(brick.size(), size, "{brick_type:?}");
+            let view_box = format!("0 0 {} {}", size.0, size.1);
+            assert_eq!(
+                brick.render(&ORANGE_SCHEME).get_attribute("viewBox"),
+                Some(view_box.as_str()),
+                "{brick_type:?}"
+            );
+        }
+    }
+}
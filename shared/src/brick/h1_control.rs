@@ -1,23 +1,35 @@
-use crate::brick::base::BrickSVG;
+use crate::brick::base::{BrickSVG, control_content_area};
+use crate::brick::geometry::{BrickGeometry, TopShape, clamp_size};
 use crate::color::ColorScheme;
-use yew::{Html, html};
+use crate::layout::ContentArea;
+use crate::svg::SvgElement;
 
 // constants
-const WIDTH: f32 = 348.181;
-const HEIGHT: f32 = 72.95;
+pub const WIDTH: f32 = 348.181;
+pub const HEIGHT: f32 = 72.95;
 
 pub struct BrickH1Control {
+    width: f32,
+    height: f32,
     content: String,
 }
 
 impl BrickH1Control {
     pub fn new() -> Self {
         Self {
+            width: WIDTH,
+            height: HEIGHT,
             content: String::new(),
         }
     }
 }
 
+impl Default for BrickH1Control {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl BrickSVG for BrickH1Control {
     fn set_content(&mut self, content: &str) {
         self.content = content.to_string();
@@ -27,67 +39,21 @@ impl BrickSVG for BrickH1Control {
         &self.content
     }
 
-    fn to_svg(&self, color_scheme: &ColorScheme) -> Html {
-        html! {
-        <svg xmlns="http://www.w3.org/2000/svg" viewBox={format!("0 0 {} {}", WIDTH, HEIGHT)}>
-             <style>
-                {format!(".background {{ fill: {}; }}", color_scheme.color)}
-                {format!(".border {{ fill: {}; }}", color_scheme.border)}
-                {format!(".shade {{ stop-color: {}; }}", color_scheme.shade)}
-            </style>
-            <defs>
-                <linearGradient id="grad1" x1="0%" x2="0%" y1="0%" y2="100%">
-                    <stop offset="0%" class="shade" stop-opacity="0.3" />
-                    <stop offset="35%" class="shade" stop-opacity="0" />
-                </linearGradient>
-                <linearGradient id="grad2" x1="0%" x2="100%" y1="0%" y2="0%">
-                    <stop offset="0%" class="shade" stop-opacity="0.125" />
-                    <stop offset="2.5%" class="shade" stop-opacity="0" />
-                </linearGradient>
-                <linearGradient id="grad3" x1="100%" x2="0%" y1="0%" y2="0%">
-                    <stop offset="0%" class="shade" stop-opacity="0.125" />
-                    <stop offset="2.5%" class="shade" stop-opacity="0" />
-                </linearGradient>
+    fn size(&self) -> (f32, f32) {
+        (self.width, self.height)
+    }
+
+    fn set_size(&mut self, width: f32, height: f32) {
+        (self.width, self.height) = clamp_size(TopShape::Control, width, height);
+    }
 
-                <g id="tabblock">
-                    <g>
-                        <rect fill-opacity="0.025" width="25.458" height="7.636" x="10.271" y="22.5" fill="#fff" />
-                        <rect width="25.458" height="7.636" x="10.271" y="22.5" fill="url(#grad1)" />
-                        <rect width="25.458" height="7.636" x="10.271" y="22.5" fill="url(#grad2)" />
-                        <rect width="25.458" height="7.636" x="10.271" y="22.5" fill="url(#grad3)" />
-                    </g>
-                </g>
-            </defs>
-            // middle scalable background
-            <g viewBox="0 10 348.181 72">
-                <path class="background" d="M0,20h348.181v48h-348.181z" />
-            </g>
-            // top
-            <g viewbox="0 0 351.887 72.748">
-                <path class="border"
-                    d="m 214 19 c -12.279 -0.277 -17.75 1 -35.25 -3.25 c -20.8 -5 -66 -15.5 -110.784 -15.748 S 0 9.35 0 9.35 v 14.73 L 350 24.222 l 0 -5.222 l -130 0 Z" />
-                <path class="background"
-                    d="M212.75,20c-12.75,0 -17.75,1 -35.25,-3.25 -20.8,-5.051 -66,-15.5 -109.75,-15.5S0,10.562 0,11L0,24.778L349,24.778L3450,20S2110,20 220,20Z" />
-                <path fill-opacity="0.4" fill="#fff"
-                    d="M 212.75 20 c -12.75 0 -17.75 1 -35.25 -3.25 c -20.8 -5.052 -66 -15.5 -110.441 -15.533 S -0.002 11 0 11 L -0.002 11.718 v 2.286 s 22.6 -10 67.039 -10.002 S 156.7 14.448 177.5 19.5 c 17.5 4.25 22.5 3.25 40.25 3.25 c 3.212 0 135.25 0 135.25 0 v -2.749 Z"
-                    stroke-opacity="0.4" />
-            </g>
-            // middle path for bars on the left
-            <g viewbox="0 0 348 69">
-                <use y="3" href="#tabblock" />
-                <use y="13.5" href="#tabblock" />
-                <use y="24" href="#tabblock" />
-            </g>
+    fn content_area(&self) -> ContentArea {
+        control_content_area(self.size())
+    }
 
-            // bottom
-            <g viewbox="0 0 351.887 72.748">
-                <path class="background"
-                    d="M0,66.661l12.318,-0.004l1.75,4.125l16.857,0.014l1.75,-4.125l12.367,-0.004l-45.042,-0.006z" />
-                <path class="border"
-                    d="M347.736,66.657l-315.056,0.001l0,0.008l-1.749,4.117l-16.858,0l-1.752,-4.126l-0.019,0.008l0,-0.007l-12.302,0l0,2l10.998,0l1.748,4.115l0.005,-0.002l0,0.012l19.502,0l0,-0.003l0.002,0.001l1.752,-4.123l317.729,-0.001l0,-2z" />
-            </g>
-            {self.content_to_svg()}
-        </svg>
-        }
+    fn render(&self, color_scheme: &ColorScheme) -> SvgElement {
+        BrickGeometry::control(self.width, self.height)
+            .render(color_scheme)
+            .child(self.content_to_svg())
     }
 }
@@ -1,7 +1,34 @@
 pub mod base;
+pub mod c_block;
+pub mod geometry;
 pub mod h0;
 pub mod h1_base;
 pub mod h1_control;
 pub mod h2_base;
 pub mod h2_control;
 pub mod h3_base;
+
+use crate::brick::base::BrickSVG;
+use crate::color::ColorScheme;
+use crate::types::BrickType;
+
+pub fn create_brick(brick_type: BrickType) -> Box<dyn BrickSVG> {
+    match brick_type {
+        BrickType::H0Collapsed => Box::new(h0::BrickH0::new()),
+        BrickType::H1Base => Box::new(h1_base::BrickH1Base::new()),
+        BrickType::H1Control => Box::new(h1_control::BrickH1Control::new()),
+        BrickType::H2Base => Box::new(h2_base::BrickH2Base::new()),
+        BrickType::H2Control => Box::new(h2_control::BrickH2Control::new()),
+        BrickType::H3Base => Box::new(h3_base::BrickH3Base::new()),
+        BrickType::If => Box::new(c_block::BrickCBlock::if_then()),
+        BrickType::IfElse => Box::new(c_block::BrickCBlock::if_else()),
+        BrickType::Repeat => Box::new(c_block::BrickCBlock::repeat()),
+        BrickType::Forever => Box::new(c_block::BrickCBlock::forever()),
+    }
+}
+
+pub fn render_svg(brick_type: BrickType, color_scheme: &ColorScheme, content: &str) -> String {
+    let mut brick = create_brick(brick_type);
+    brick.set_content(content);
+    brick.to_svg_string(color_scheme)
+}
@@ -0,0 +1,364 @@
+use crate::category::{ALL_BRICK_CATEGORIES, BrickCategory};
+use crate::content::{ContentPart, parse_content, to_markup};
+use crate::document::BrickSpec;
+use crate::types::BrickType;
+use serde::Deserialize;
+use std::sync::OnceLock;
+
+// The bricks of Catrobat as data, read from catalog/bricks.json. Adding a
+// brick only takes a new entry there, no code. Ids are the class names
+// Catrobat uses in its project files, e.g. "PlaceAtBrick".
+//
+// 1: id, name, category, type, content and description
+// 2: names of the formulas and fields of Catrobat behind the slots
+pub const CATALOG_VERSION: u32 = 2;
+
+const CATALOG_JSON: &str = include_str!("../catalog/bricks.json");
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct CatalogBrick {
+    pub id: String,
+    pub name: String,
+    pub category: BrickCategory,
+    #[serde(rename = "type")]
+    pub brick_type: BrickType,
+    // default content in the markup of the content module, with its
+    // parameter slots
+    pub content: String,
+    pub description: String,
+    // Catrobat formula categories of the value slots, in order, e.g.
+    // "X_POSITION"
+    #[serde(default)]
+    pub formulas: Vec<String>,
+    // Catrobat elements behind the dropdown and variable slots, in order,
+    // e.g. "userVariable"
+    #[serde(default)]
+    pub fields: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct Catalog {
+    version: u32,
+    bricks: Vec<CatalogBrick>,
+}
+
+impl CatalogBrick {
+    // a brick of the entry, in the colors of its category
+    pub fn to_spec(&self) -> BrickSpec {
+        BrickSpec::new(self.brick_type, self.category.color_scheme(), &self.content)
+    }
+
+    // the value, dropdown and variable slots of the default content
+    pub fn parameters(&self) -> Vec<ContentPart> {
+        parse_content(&self.content)
+            .into_iter()
+            .filter(|part| !matches!(part, ContentPart::Text(_)))
+            .collect()
+    }
+
+    // The default content with the slots filled in. Value slots take the
+    // values in order, dropdown and variable slots the fields; missing ones
+    // keep their default.
+    pub fn content_with(&self, values: &[Option<String>], fields: &[Option<String>]) -> String {
+        let (mut values, mut fields) = (values.iter(), fields.iter());
+        let parts: Vec<ContentPart> = parse_content(&self.content)
+            .into_iter()
+            .map(|part| {
+                let filled = match &part {
+                    ContentPart::Text(_) => None,
+                    ContentPart::Value(_) => values.next(),
+                    ContentPart::Dropdown(_) | ContentPart::Variable(_) => fields.next(),
+                };
+                let Some(Some(label)) = filled else {
+                    return part;
+                };
+                match part {
+                    ContentPart::Value(_) => ContentPart::Value(label.clone()),
+                    ContentPart::Dropdown(_) => ContentPart::Dropdown(label.clone()),
+                    _ => ContentPart::Variable(label.clone()),
+                }
+            })
+            .collect();
+        to_markup(&parts)
+    }
+
+    // The slot labels of content written for this brick, values and fields
+    // apart, or None when the text around the slots differs. The inverse of
+    // `content_with`, ignoring case and runs of spaces.
+    pub fn slot_values(&self, content: &str) -> Option<(Vec<String>, Vec<String>)> {
+        let skeleton = |markup: &str| -> Vec<ContentPart> {
+            parse_content(markup)
+                .into_iter()
+                .filter_map(|part| match part {
+                    ContentPart::Text(text) => {
+                        let words: Vec<&str> = text.split_whitespace().collect();
+                        (!words.is_empty())
+                            .then(|| ContentPart::Text(words.join(" ").to_lowercase()))
+                    }
+                    slot => Some(slot),
+                })
+                .collect()
+        };
+        let (expected, actual) = (skeleton(&self.content), skeleton(content));
+        if expected.len() != actual.len() {
+            return None;
+        }
+        let (mut values, mut fields) = (Vec::new(), Vec::new());
+        for (expected, actual) in expected.iter().zip(actual) {
+            match (expected, actual) {
+                (ContentPart::Text(expected), ContentPart::Text(actual)) if *expected == actual => {
+                }
+                (ContentPart::Value(_), ContentPart::Value(value)) => values.push(value),
+                (ContentPart::Dropdown(_), ContentPart::Dropdown(field))
+                | (ContentPart::Variable(_), ContentPart::Variable(field)) => fields.push(field),
+                _ => return None,
+            }
+        }
+        Some((values, fields))
+    }
+
+    // every term has to appear in the name, the id, the description or the
+    // category, ignoring case
+    pub fn matches(&self, query: &str) -> bool {
+        let haystack = [
+            self.name.as_str(),
+            self.id.as_str(),
+            self.description.as_str(),
+            self.category.name(),
+        ]
+        .join("\n")
+        .to_lowercase();
+        query
+            .to_lowercase()
+            .split_whitespace()
+            .all(|term| haystack.contains(term))
+    }
+}
+
+fn parse_catalog(text: &str) -> Result<Vec<CatalogBrick>, String> {
+    let catalog: Catalog = serde_json::from_str(text).map_err(|error| error.to_string())?;
+    if catalog.version != CATALOG_VERSION {
+        return Err(format!("unsupported catalog version {}", catalog.version));
+    }
+    for brick in &catalog.bricks {
+        if !brick.category.allows(brick.brick_type) {
+            return Err(format!(
+                "brick '{}' has the shape {}, which the category {} does not use",
+                brick.id,
+                brick.brick_type.id(),
+                brick.category.id()
+            ));
+        }
+        let parameters = brick.parameters();
+        let values = parameters
+            .iter()
+            .filter(|part| matches!(part, ContentPart::Value(_)))
+            .count();
+        if brick.formulas.len() > values || brick.fields.len() > parameters.len() - values {
+            return Err(format!(
+                "brick '{}' names more formulas or fields than it has slots",
+                brick.id
+            ));
+        }
+    }
+    Ok(catalog.bricks)
+}
+
+// all bricks, grouped by category in the order of the categories
+pub fn catalog() -> &'static [CatalogBrick] {
+    static CATALOG: OnceLock<Vec<CatalogBrick>> = OnceLock::new();
+    CATALOG.get_or_init(|| {
+        // the file is part of the build, so an error is a bug of this crate
+        let mut bricks = parse_catalog(CATALOG_JSON).expect("invalid catalog/bricks.json");
+        bricks.sort_by_key(|brick| {
+            ALL_BRICK_CATEGORIES
+                .iter()
+                .position(|category| *category == brick.category)
+        });
+        bricks
+    })
+}
+
+pub fn find_catalog_brick(id: &str) -> Option<&'static CatalogBrick> {
+    catalog().iter().find(|brick| brick.id == id)
+}
+
+// The catalog brick whose text the content has, preferring one of the given
+// shape, e.g. a "Repeat {3} times" brick made by hand.
+pub fn match_catalog_brick(
+    content: &str,
+    brick_type: BrickType,
+) -> Option<(&'static CatalogBrick, Vec<String>, Vec<String>)> {
+    let mut matches = catalog().iter().filter_map(|brick| {
+        brick
+            .slot_values(content)
+            .map(|(values, fields)| (brick, values, fields))
+    });
+    let first = matches.next()?;
+    if first.0.brick_type == brick_type {
+        return Some(first);
+    }
+    matches
+        .find(|(brick, _, _)| brick.brick_type == brick_type)
+        .or(Some(first))
+}
+
+// the bricks matching all terms of the query, all of them for an empty one
+pub fn search_catalog(query: &str) -> Vec<&'static CatalogBrick> {
+    catalog()
+        .iter()
+        .filter(|brick| brick.matches(query))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalog_is_valid() {
+        let bricks = parse_catalog(CATALOG_JSON).unwrap();
+        for (index, brick) in bricks.iter().enumerate() {
+            assert!(
+                bricks[..index].iter().all(|other| other.id != brick.id),
+                "duplicate brick id '{}'",
+                brick.id
+            );
+        }
+        for category in ALL_BRICK_CATEGORIES {
+            assert!(
+                bricks.iter().any(|brick| brick.category == *category),
+                "no bricks in category {}",
+                category.id()
+            );
+        }
+    }
+
+    fn brick(id: &str) -> &'static CatalogBrick {
+        find_catalog_brick(id).unwrap()
+    }
+
+    fn strings(labels: &[&str]) -> Vec<String> {
+        labels.iter().map(|label| label.to_string()).collect()
+    }
+
+    #[test]
+    fn slots_are_filled_in_order() {
+        let place_at = brick("PlaceAtBrick");
+        assert_eq!(
+            place_at.content_with(&[Some("1".into()), Some("x + 2".into())], &[]),
+            "Place at X: {1} Y: {x + 2}"
+        );
+        // missing values keep their default
+        assert_eq!(
+            place_at.content_with(&[None], &[]),
+            "Place at X: {100} Y: {200}"
+        );
+        let set_variable = brick("SetVariableBrick");
+        assert_eq!(
+            set_variable.content_with(&[Some("0".into())], &[Some("score".into())]),
+            "Set <score> to {0}"
+        );
+        // labels containing delimiters are escaped, not cut
+        assert_eq!(
+            set_variable.content_with(&[Some("join(\"}\", 1)".into())], &[Some("a>b".into())]),
+            r#"Set <a\>b> to {join("\}", 1)}"#
+        );
+    }
+
+    #[test]
+    fn slot_values_read_filled_content() {
+        let glide = brick("GlideToBrick");
+        assert_eq!(
+            glide.slot_values("glide  {2} SECOND to x: {a} y: {b + 1}"),
+            Some((strings(&["2", "a", "b + 1"]), vec![]))
+        );
+        assert_eq!(
+            glide.slot_values("Glide {2} seconds to X: {1} Y: {2}"),
+            None
+        );
+        assert_eq!(glide.slot_values("Glide {2} second to X: {1}"), None);
+        // a value where the brick has a dropdown
+        assert_eq!(
+            brick("PlaySoundBrick").slot_values("Start sound {meow}"),
+            None
+        );
+
+        for (id, values, fields) in [
+            ("PlaceAtBrick", vec![Some("-5".into()), None], vec![]),
+            (
+                "SetVariableBrick",
+                vec![Some("{1}".into())],
+                vec![Some("x] <y>".into())],
+            ),
+            ("PlaySoundBrick", vec![], vec![Some("sound \\ 2".into())]),
+        ] {
+            let brick = brick(id);
+            let content = brick.content_with(&values, &fields);
+            let (read_values, read_fields) = brick.slot_values(&content).unwrap();
+            let defaults = brick.parameters();
+            let expected = |filled: &[Option<String>], kind: fn(&ContentPart) -> bool| {
+                let mut filled = filled.iter();
+                defaults
+                    .iter()
+                    .filter(|part| kind(part))
+                    .map(|part| {
+                        filled
+                            .next()
+                            .cloned()
+                            .flatten()
+                            .unwrap_or_else(|| part.label().to_string())
+                    })
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(
+                read_values,
+                expected(&values, |part| matches!(part, ContentPart::Value(_)))
+            );
+            assert_eq!(
+                read_fields,
+                expected(&fields, |part| !matches!(part, ContentPart::Value(_)))
+            );
+        }
+    }
+
+    #[test]
+    fn matching_prefers_the_given_shape() {
+        let (found, values, fields) =
+            match_catalog_brick("Repeat {3} times", BrickType::H1Base).unwrap();
+        assert_eq!(found.id, "RepeatBrick");
+        assert_eq!((values, fields), (strings(&["3"]), vec![]));
+
+        let content = "If {x > 1} is true then";
+        let (found, ..) = match_catalog_brick(content, BrickType::If).unwrap();
+        assert_eq!(found.id, "IfThenLogicBeginBrick");
+        let (found, values, _) = match_catalog_brick(content, BrickType::IfElse).unwrap();
+        assert_eq!(found.id, "IfLogicBeginBrick");
+        assert_eq!(values, strings(&["x > 1"]));
+        // without a brick of the shape the first match is used
+        let (found, ..) = match_catalog_brick(content, BrickType::H1Base).unwrap();
+        assert!(found.id.starts_with("If"));
+
+        assert!(match_catalog_brick("Jump around", BrickType::H1Base).is_none());
+    }
+
+    #[test]
+    fn search_needs_every_term() {
+        let ids = |query: &str| -> Vec<&str> {
+            search_catalog(query)
+                .iter()
+                .map(|brick| brick.id.as_str())
+                .collect()
+        };
+        assert_eq!(ids("").len(), catalog().len());
+        assert_eq!(ids("   ").len(), catalog().len());
+        assert!(ids("repeat").contains(&"RepeatUntilBrick"));
+        assert!(ids("repeat").contains(&"RepeatBrick"));
+        let until = ids("REPEAT until");
+        assert!(until.contains(&"RepeatUntilBrick") && !until.contains(&"RepeatBrick"));
+        // terms match across the name, id and category
+        assert!(ids("glide motion").contains(&"GlideToBrick"));
+        assert!(ids("glide sound").is_empty());
+        assert!(ids("PlaceAtBrick").contains(&"PlaceAtBrick"));
+    }
+}
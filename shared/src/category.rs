@@ -0,0 +1,238 @@
+use crate::color::{
+    BLUE_SCHEME, CYAN_SCHEME, ColorScheme, DARK_BLUE_SCHEME, DARK_GREEN_SCHEME, GOLD_SCHEME,
+    GREEN_SCHEME, LIGHT_ORANGE_SCHEME, OLIVE_SCHEME, ORANGE_SCHEME, PINK_SCHEME, RED_SCHEME,
+    VIOLET_SCHEME, WINERED_SCHEME, YELLOW_SCHEME,
+};
+use crate::types::{BrickType, serde_by_id};
+
+// The brick categories of Catrobat. Every category has its own color scheme,
+// and only some shapes appear in it: scripts start with the hat bricks of
+// the event category, and the C-shaped bricks belong to control.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BrickCategory {
+    Event,
+    Control,
+    Motion,
+    Looks,
+    Sound,
+    Pen,
+    Data,
+    UserBricks,
+    Embroidery,
+    Lego,
+    Arduino,
+    Drone,
+    RaspberryPi,
+    Testing,
+}
+
+pub const ALL_BRICK_CATEGORIES: &[BrickCategory] = &[
+    BrickCategory::Event,
+    BrickCategory::Control,
+    BrickCategory::Motion,
+    BrickCategory::Looks,
+    BrickCategory::Sound,
+    BrickCategory::Pen,
+    BrickCategory::Data,
+    BrickCategory::UserBricks,
+    BrickCategory::Embroidery,
+    BrickCategory::Lego,
+    BrickCategory::Arduino,
+    BrickCategory::Drone,
+    BrickCategory::RaspberryPi,
+    BrickCategory::Testing,
+];
+
+const BASE_TYPES: &[BrickType] = &[
+    BrickType::H1Base,
+    BrickType::H2Base,
+    BrickType::H3Base,
+    BrickType::H0Collapsed,
+];
+
+// hats first, broadcasting bricks are events too
+const EVENT_TYPES: &[BrickType] = &[
+    BrickType::H1Control,
+    BrickType::H2Control,
+    BrickType::H1Base,
+    BrickType::H2Base,
+    BrickType::H0Collapsed,
+];
+
+const CONTROL_TYPES: &[BrickType] = &[
+    BrickType::H1Base,
+    BrickType::H2Base,
+    BrickType::H3Base,
+    BrickType::If,
+    BrickType::IfElse,
+    BrickType::Repeat,
+    BrickType::Forever,
+    BrickType::H0Collapsed,
+];
+
+// user defined bricks have a definition hat next to the bricks that use it
+const USER_BRICK_TYPES: &[BrickType] = &[
+    BrickType::H1Base,
+    BrickType::H2Base,
+    BrickType::H3Base,
+    BrickType::H1Control,
+    BrickType::H2Control,
+    BrickType::H0Collapsed,
+];
+
+impl BrickCategory {
+    // stable identifier used in files, e.g. "UserBricks"
+    pub const fn id(self) -> &'static str {
+        match self {
+            Self::Event => "Event",
+            Self::Control => "Control",
+            Self::Motion => "Motion",
+            Self::Looks => "Looks",
+            Self::Sound => "Sound",
+            Self::Pen => "Pen",
+            Self::Data => "Data",
+            Self::UserBricks => "UserBricks",
+            Self::Embroidery => "Embroidery",
+            Self::Lego => "Lego",
+            Self::Arduino => "Arduino",
+            Self::Drone => "Drone",
+            Self::RaspberryPi => "RaspberryPi",
+            Self::Testing => "Testing",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        ALL_BRICK_CATEGORIES
+            .iter()
+            .copied()
+            .find(|category| category.id().eq_ignore_ascii_case(id))
+    }
+
+    // name as Catrobat shows it
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Event => "Event",
+            Self::Control => "Control",
+            Self::Motion => "Motion",
+            Self::Looks => "Looks",
+            Self::Sound => "Sound",
+            Self::Pen => "Pen",
+            Self::Data => "Data",
+            Self::UserBricks => "Your bricks",
+            Self::Embroidery => "Embroidery",
+            Self::Lego => "Lego",
+            Self::Arduino => "Arduino",
+            Self::Drone => "Drone",
+            Self::RaspberryPi => "Raspberry Pi",
+            Self::Testing => "Testing",
+        }
+    }
+
+    pub const fn color_scheme(self) -> ColorScheme {
+        match self {
+            Self::Event => ORANGE_SCHEME,
+            Self::Control => LIGHT_ORANGE_SCHEME,
+            Self::Motion => BLUE_SCHEME,
+            Self::Looks => GREEN_SCHEME,
+            Self::Sound => VIOLET_SCHEME,
+            Self::Pen => DARK_GREEN_SCHEME,
+            Self::Data => RED_SCHEME,
+            Self::UserBricks => PINK_SCHEME,
+            Self::Embroidery => CYAN_SCHEME,
+            Self::Lego => YELLOW_SCHEME,
+            Self::Arduino => OLIVE_SCHEME,
+            Self::Drone => DARK_BLUE_SCHEME,
+            Self::RaspberryPi => WINERED_SCHEME,
+            Self::Testing => GOLD_SCHEME,
+        }
+    }
+
+    // shapes the bricks of the category come in, the most common first
+    pub const fn brick_types(self) -> &'static [BrickType] {
+        match self {
+            Self::Event => EVENT_TYPES,
+            Self::Control => CONTROL_TYPES,
+            Self::UserBricks => USER_BRICK_TYPES,
+            _ => BASE_TYPES,
+        }
+    }
+
+    pub fn allows(self, brick_type: BrickType) -> bool {
+        self.brick_types().contains(&brick_type)
+    }
+
+    // the category a scheme is the default of, if any
+    pub fn of_color_scheme(color_scheme: &ColorScheme) -> Option<Self> {
+        ALL_BRICK_CATEGORIES
+            .iter()
+            .copied()
+            .find(|category| category.color_scheme() == *color_scheme)
+    }
+}
+
+serde_by_id!(BrickCategory, "brick category");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+
+    #[test]
+    fn categories_allow_their_shapes() {
+        for category in ALL_BRICK_CATEGORIES {
+            assert!(category.allows(BrickType::H1Base), "{category:?}");
+            assert!(category.allows(BrickType::H0Collapsed), "{category:?}");
+        }
+        assert!(BrickCategory::Event.allows(BrickType::H1Control));
+        assert!(!BrickCategory::Event.allows(BrickType::Repeat));
+        assert!(BrickCategory::Control.allows(BrickType::IfElse));
+        assert!(!BrickCategory::Control.allows(BrickType::H1Control));
+        assert!(BrickCategory::UserBricks.allows(BrickType::H2Control));
+        assert!(!BrickCategory::Motion.allows(BrickType::H1Control));
+        assert!(!BrickCategory::Motion.allows(BrickType::Forever));
+    }
+
+    #[test]
+    fn every_category_has_its_own_scheme() {
+        assert_eq!(BrickCategory::Event.color_scheme(), ORANGE_SCHEME);
+        assert_eq!(BrickCategory::Motion.color_scheme(), BLUE_SCHEME);
+        for category in ALL_BRICK_CATEGORIES {
+            assert_eq!(
+                BrickCategory::of_color_scheme(&category.color_scheme()),
+                Some(*category)
+            );
+        }
+        let custom = ColorScheme::derive("Mint", Color::hex("#2a9d8f"));
+        assert_eq!(BrickCategory::of_color_scheme(&custom), None);
+    }
+
+    #[test]
+    fn ids_round_trip() {
+        for category in ALL_BRICK_CATEGORIES {
+            assert_eq!(BrickCategory::from_id(category.id()), Some(*category));
+            let json = serde_json::to_string(category).unwrap();
+            assert_eq!(json, format!("\"{}\"", category.id()));
+            assert_eq!(
+                serde_json::from_str::<BrickCategory>(&json).unwrap(),
+                *category
+            );
+        }
+        assert_eq!(
+            BrickCategory::from_id("raspberrypi"),
+            Some(BrickCategory::RaspberryPi)
+        );
+        assert_eq!(
+            serde_json::from_str::<BrickCategory>(r#""USERBRICKS""#).unwrap(),
+            BrickCategory::UserBricks
+        );
+        assert_eq!(BrickCategory::from_id("Raspberry Pi"), None);
+        let error = serde_json::from_str::<BrickCategory>(r#""Sounds""#).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("unknown brick category 'Sounds'")
+        );
+        let error = serde_json::from_str::<BrickType>(r#""H4Base""#).unwrap_err();
+        assert!(error.to_string().contains("unknown brick type 'H4Base'"));
+    }
+}
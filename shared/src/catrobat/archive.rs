@@ -0,0 +1,344 @@
+use super::CatrobatError;
+
+// Just enough of the ZIP format for Catrobat projects: stored and deflated
+// entries without encryption or ZIP64, read and written. Deflate comes from
+// miniz_oxide, which the share links use as well.
+
+const LOCAL_HEADER: u32 = 0x0403_4b50;
+const CENTRAL_HEADER: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x0605_4b50;
+const STORED: u16 = 0;
+const DEFLATED: u16 = 8;
+// the date 1980-01-01 in MS-DOS format, the earliest a ZIP archive can hold
+const DOS_EPOCH: u16 = (1 << 5) | 1;
+// inflated entries larger than this are rejected
+const MAX_ENTRY_BYTES: usize = 64 << 20;
+
+struct Entry<'a> {
+    name: String,
+    method: u16,
+    data: &'a [u8],
+}
+
+// Offsets and lengths come from the archive, so every sum is checked and a
+// range outside the archive is an error instead of a panic.
+fn range(start: usize, length: usize) -> Option<std::ops::Range<usize>> {
+    Some(start..start.checked_add(length)?)
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> Option<u16> {
+    let bytes = bytes.get(range(offset, 2)?)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> Option<u32> {
+    let bytes = bytes.get(range(offset, 4)?)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn invalid(message: &str) -> CatrobatError {
+    CatrobatError::Archive(message.to_string())
+}
+
+// the directory at the end of the archive, found by scanning back over the
+// comment that may follow it
+fn entries(bytes: &[u8]) -> Result<Vec<Entry<'_>>, CatrobatError> {
+    let end = (0..=bytes.len().saturating_sub(22))
+        .rev()
+        .find(|offset| u32_at(bytes, *offset) == Some(END_OF_CENTRAL_DIRECTORY))
+        .ok_or_else(|| invalid("not a ZIP archive"))?;
+    let count = u16_at(bytes, end + 10).ok_or_else(|| invalid("truncated archive"))?;
+    let mut offset = u32_at(bytes, end + 16).ok_or_else(|| invalid("truncated archive"))? as usize;
+
+    let mut entries = Vec::with_capacity(count.into());
+    for _ in 0..count {
+        let truncated = || invalid("truncated central directory");
+        let field = |field: usize| offset.checked_add(field).ok_or_else(truncated);
+        let read_u16 = |at: usize| -> Result<usize, CatrobatError> {
+            u16_at(bytes, field(at)?)
+                .map(usize::from)
+                .ok_or_else(truncated)
+        };
+        let read_u32 = |at: usize| -> Result<usize, CatrobatError> {
+            u32_at(bytes, field(at)?)
+                .map(|value| value as usize)
+                .ok_or_else(truncated)
+        };
+        if u32_at(bytes, offset) != Some(CENTRAL_HEADER) {
+            return Err(invalid("broken central directory"));
+        }
+        let method = u16_at(bytes, field(10)?).ok_or_else(truncated)?;
+        let compressed = read_u32(20)?;
+        let name_length = read_u16(28)?;
+        let extra_length = read_u16(30)?;
+        let comment_length = read_u16(32)?;
+        let local = read_u32(42)?;
+        let name = bytes
+            .get(range(field(46)?, name_length).ok_or_else(truncated)?)
+            .ok_or_else(truncated)?;
+        if compressed == u32::MAX as usize || local == u32::MAX as usize {
+            return Err(invalid("ZIP64 archives are not supported"));
+        }
+
+        // the local header repeats the name and has its own extra field
+        if u32_at(bytes, local) != Some(LOCAL_HEADER) {
+            return Err(invalid("broken local header"));
+        }
+        let truncated_entry = || invalid("truncated entry");
+        let local_field = |at: usize| local.checked_add(at).ok_or_else(truncated_entry);
+        let local_name = u16_at(bytes, local_field(26)?).ok_or_else(truncated_entry)?;
+        let local_extra = u16_at(bytes, local_field(28)?).ok_or_else(truncated_entry)?;
+        let start = local_field(30)?
+            .checked_add(usize::from(local_name) + usize::from(local_extra))
+            .ok_or_else(truncated_entry)?;
+        let data = bytes
+            .get(range(start, compressed).ok_or_else(truncated_entry)?)
+            .ok_or_else(truncated_entry)?;
+
+        entries.push(Entry {
+            name: String::from_utf8_lossy(name).into_owned(),
+            method,
+            data,
+        });
+        offset = field(46)?
+            .checked_add(name_length + extra_length + comment_length)
+            .ok_or_else(truncated)?;
+    }
+    Ok(entries)
+}
+
+// names of the files in the archive, directories end with '/'
+pub fn entry_names(bytes: &[u8]) -> Result<Vec<String>, CatrobatError> {
+    Ok(entries(bytes)?
+        .into_iter()
+        .map(|entry| entry.name)
+        .collect())
+}
+
+pub fn read_entry(bytes: &[u8], name: &str) -> Result<Option<Vec<u8>>, CatrobatError> {
+    let Some(entry) = entries(bytes)?.into_iter().find(|entry| entry.name == name) else {
+        return Ok(None);
+    };
+    match entry.method {
+        STORED => Ok(Some(entry.data.to_vec())),
+        DEFLATED => miniz_oxide::inflate::decompress_to_vec_with_limit(entry.data, MAX_ENTRY_BYTES)
+            .map(Some)
+            .map_err(|error| {
+                CatrobatError::Archive(format!("could not inflate '{name}': {error}"))
+            }),
+        method => Err(CatrobatError::Archive(format!(
+            "'{name}' uses the unsupported compression method {method}"
+        ))),
+    }
+}
+
+// CRC-32 as ZIP uses it, bit by bit since archives are small
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = u32::MAX;
+    for byte in data {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+// An archive of the files in the given order. Names ending in '/' become
+// directories, files are deflated.
+pub fn write_archive(files: &[(&str, &[u8])]) -> Result<Vec<u8>, CatrobatError> {
+    let too_large = || invalid("the project is too large for a ZIP archive without ZIP64");
+    let mut archive = Vec::new();
+    let mut directory = Vec::new();
+    for (name, data) in files {
+        let is_directory = name.ends_with('/');
+        let (method, stored) = if is_directory {
+            (STORED, Vec::new())
+        } else {
+            (DEFLATED, miniz_oxide::deflate::compress_to_vec(data, 6))
+        };
+        let crc = crc32(data);
+        let name_length = u16::try_from(name.len()).map_err(|_| too_large())?;
+        let compressed = u32::try_from(stored.len()).map_err(|_| too_large())?;
+        let size = u32::try_from(data.len()).map_err(|_| too_large())?;
+        let offset = u32::try_from(archive.len()).map_err(|_| too_large())?;
+
+        // version 2.0, UTF-8 names, a fixed timestamp of 1980-01-01 00:00 so
+        // the same script always gives the same archive
+        let common = |header: &mut Vec<u8>| {
+            header.extend(20u16.to_le_bytes());
+            header.extend(0x0800u16.to_le_bytes());
+            header.extend(method.to_le_bytes());
+            header.extend(0u16.to_le_bytes());
+            header.extend(DOS_EPOCH.to_le_bytes());
+            header.extend(crc.to_le_bytes());
+            header.extend(compressed.to_le_bytes());
+            header.extend(size.to_le_bytes());
+            header.extend(name_length.to_le_bytes());
+            header.extend([0; 2]);
+        };
+        archive.extend(LOCAL_HEADER.to_le_bytes());
+        common(&mut archive);
+        archive.extend(name.as_bytes());
+        archive.extend(&stored);
+
+        directory.extend(CENTRAL_HEADER.to_le_bytes());
+        directory.extend(20u16.to_le_bytes());
+        common(&mut directory);
+        // comment, disk, internal and external attributes
+        directory.extend([0; 6]);
+        let attributes: u32 = if is_directory { 0x10 } else { 0 };
+        directory.extend(attributes.to_le_bytes());
+        directory.extend(offset.to_le_bytes());
+        directory.extend(name.as_bytes());
+    }
+
+    let count = u16::try_from(files.len()).map_err(|_| too_large())?;
+    let directory_offset = u32::try_from(archive.len()).map_err(|_| too_large())?;
+    let directory_size = u32::try_from(directory.len()).map_err(|_| too_large())?;
+    archive.extend(directory);
+    archive.extend(END_OF_CENTRAL_DIRECTORY.to_le_bytes());
+    archive.extend([0; 4]);
+    archive.extend(count.to_le_bytes());
+    archive.extend(count.to_le_bytes());
+    archive.extend(directory_size.to_le_bytes());
+    archive.extend(directory_offset.to_le_bytes());
+    archive.extend([0; 2]);
+    Ok(archive)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XML: &[u8] = b"<?xml version=\"1.0\"?><program><scenes/></program>";
+
+    fn archive() -> Vec<u8> {
+        write_archive(&[
+            ("code.xml", XML),
+            ("Scene 1/images/", &[]),
+            ("Scene 1/sounds/", &[]),
+        ])
+        .unwrap()
+    }
+
+    // offset of the first central directory header
+    fn directory(archive: &[u8]) -> usize {
+        let end = archive.len() - 22;
+        u32_at(archive, end + 16).unwrap() as usize
+    }
+
+    // an archive with one stored entry, as other ZIP tools write them
+    fn stored(name: &str, data: &[u8]) -> Vec<u8> {
+        let mut header = Vec::new();
+        header.extend(20u16.to_le_bytes());
+        header.extend(0u16.to_le_bytes());
+        header.extend(STORED.to_le_bytes());
+        header.extend([0; 4]);
+        header.extend(crc32(data).to_le_bytes());
+        header.extend((data.len() as u32).to_le_bytes());
+        header.extend((data.len() as u32).to_le_bytes());
+        header.extend((name.len() as u16).to_le_bytes());
+        header.extend(0u16.to_le_bytes());
+
+        let mut archive = LOCAL_HEADER.to_le_bytes().to_vec();
+        archive.extend(&header);
+        archive.extend(name.as_bytes());
+        archive.extend(data);
+        let directory_offset = archive.len() as u32;
+        archive.extend(CENTRAL_HEADER.to_le_bytes());
+        archive.extend(20u16.to_le_bytes());
+        archive.extend(&header);
+        archive.extend([0; 10]);
+        archive.extend(0u32.to_le_bytes());
+        archive.extend(name.as_bytes());
+        let directory_size = archive.len() as u32 - directory_offset;
+        archive.extend(END_OF_CENTRAL_DIRECTORY.to_le_bytes());
+        archive.extend([0; 4]);
+        archive.extend(1u16.to_le_bytes());
+        archive.extend(1u16.to_le_bytes());
+        archive.extend(directory_size.to_le_bytes());
+        archive.extend(directory_offset.to_le_bytes());
+        archive.extend([0; 2]);
+        archive
+    }
+
+    #[test]
+    fn written_archives_read_back() {
+        let archive = archive();
+        assert_eq!(
+            entry_names(&archive).unwrap(),
+            ["code.xml", "Scene 1/images/", "Scene 1/sounds/"]
+        );
+        let methods: Vec<u16> = entries(&archive)
+            .unwrap()
+            .iter()
+            .map(|entry| entry.method)
+            .collect();
+        assert_eq!(methods, [DEFLATED, STORED, STORED]);
+        assert_eq!(read_entry(&archive, "code.xml").unwrap().unwrap(), XML);
+        assert_eq!(
+            read_entry(&archive, "Scene 1/images/").unwrap().unwrap(),
+            b""
+        );
+        assert_eq!(read_entry(&archive, "missing.xml").unwrap(), None);
+        // the same files always give the same archive
+        assert_eq!(archive, self::archive());
+    }
+
+    #[test]
+    fn stored_entries_are_read() {
+        let archive = stored("code.xml", XML);
+        assert_eq!(entries(&archive).unwrap()[0].method, STORED);
+        assert_eq!(read_entry(&archive, "code.xml").unwrap().unwrap(), XML);
+    }
+
+    #[test]
+    fn crc32_matches_zip() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn broken_archives_are_errors() {
+        for bytes in [&b""[..], b"PK", b"not a zip archive at all, just some text"] {
+            assert!(read_entry(bytes, "code.xml").is_err());
+        }
+        let archive = archive();
+        for length in 0..archive.len() {
+            assert!(
+                read_entry(&archive[..length], "code.xml").is_err(),
+                "{length}"
+            );
+        }
+    }
+
+    #[test]
+    fn header_fields_out_of_range_are_errors() {
+        let archive = archive();
+        let directory = directory(&archive);
+        // local header offset, compressed size and name length of the first
+        // entry, each pointing far beyond the archive
+        for (field, value) in [(42, 0xffff_fffe_u32), (20, 0xffff_fffe), (28, 0xffff)] {
+            let mut broken = archive.clone();
+            let at = directory + field;
+            let bytes = value.to_le_bytes();
+            let width = if field == 28 { 2 } else { 4 };
+            broken[at..at + width].copy_from_slice(&bytes[..width]);
+            assert!(read_entry(&broken, "code.xml").is_err(), "{field}");
+        }
+    }
+
+    #[test]
+    fn zip64_is_rejected() {
+        let mut archive = archive();
+        let at = directory(&archive) + 20;
+        archive[at..at + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        let error = read_entry(&archive, "code.xml").unwrap_err();
+        assert!(error.to_string().contains("ZIP64"), "{error}");
+    }
+}
@@ -0,0 +1,521 @@
+use super::archive::write_archive;
+use super::formula::{Formula, parse_formula};
+use super::{CODE_XML, CatrobatError, slot_lists};
+use crate::catalog::{CatalogBrick, find_catalog_brick, match_catalog_brick};
+use crate::content::{ContentPart, parse_content};
+use crate::script::{Script, ScriptBrick};
+use std::fmt;
+
+// The language version the written code.xml follows, with the nested brick
+// lists Pocket Code writes since scenes were introduced.
+pub const CATROBAT_LANGUAGE_VERSION: &str = "1.12";
+
+const SCENE: &str = "Scene 1";
+const BACKGROUND: &str = "Background";
+// what bricks without a catalog entry and scripts without a hat become
+const NOTE: &str = "NoteBrick";
+const START: &str = "StartScript";
+// fields that point to files or objects, which the exported project has none of
+const UNSET_FIELDS: &[&str] = &["look", "sound", "objectToClone", "pointedObject"];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExportWarning {
+    // no catalog brick has the text of the brick, it became a note
+    Unmatched(String),
+    // the script does not start with a hat brick, it got "When scene starts"
+    NoHat,
+    // the project has no look, sound or object for the dropdown to name
+    UnsetField { brick: String, field: String },
+}
+
+impl fmt::Display for ExportWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unmatched(text) => {
+                write!(
+                    f,
+                    "no Catrobat brick reads '{text}', it is exported as a note"
+                )
+            }
+            Self::NoHat => write!(
+                f,
+                "the script does not start with a hat brick, it starts when the scene starts"
+            ),
+            Self::UnsetField { brick, field } => write!(
+                f,
+                "{brick}: the exported project has no {field} to select, pick one in Pocket Code"
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CatrobatExport {
+    pub code_xml: String,
+    pub warnings: Vec<ExportWarning>,
+}
+
+impl CatrobatExport {
+    // a minimal project: code.xml and the empty media directories of its scene
+    pub fn to_archive(&self) -> Result<Vec<u8>, CatrobatError> {
+        let images = format!("{SCENE}/images/");
+        let sounds = format!("{SCENE}/sounds/");
+        write_archive(&[
+            (CODE_XML, self.code_xml.as_bytes()),
+            (&images, &[]),
+            (&sounds, &[]),
+        ])
+    }
+}
+
+// Writes the script as the program of a project with one object called
+// `name`. Every hat brick starts a script of its own. Bricks are matched to
+// the catalog by their text, and their slots become formulas and fields.
+pub fn export_catrobat(script: &Script, name: &str) -> CatrobatExport {
+    let mut exporter = Exporter::default();
+    let mut xml = Xml::default();
+    xml.open("program", &[]);
+    header(&mut xml, name);
+    xml.empty("settings");
+    xml.open("scenes", &[]);
+    xml.open("scene", &[]);
+    xml.leaf("name", SCENE);
+    xml.open("objectList", &[]);
+    object(&mut xml, BACKGROUND, |_| {});
+    object(&mut xml, name, |xml| {
+        for (hat, body) in exporter.split(&script.bricks) {
+            exporter.script(xml, hat, &body);
+        }
+    });
+    xml.close("objectList");
+    xml.open("data", &[]);
+    for list in [
+        "objectListOfList",
+        "objectVariableList",
+        "userBrickVariableList",
+    ] {
+        xml.empty(list);
+    }
+    xml.close("data");
+    xml.leaf("originalHeight", "1920");
+    xml.leaf("originalWidth", "1080");
+    xml.close("scene");
+    xml.close("scenes");
+    // the variables and lists the bricks use, as globals
+    xml.open("programVariableList", &[]);
+    for variable in &exporter.variables {
+        variable_element(&mut xml, "userVariable", variable);
+    }
+    xml.close("programVariableList");
+    xml.open("programListOfLists", &[]);
+    for list in &exporter.lists {
+        variable_element(&mut xml, "userList", list);
+    }
+    xml.close("programListOfLists");
+    xml.close("program");
+
+    // the warnings travel along as comments for whoever opens the file
+    let mut code_xml =
+        String::from("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n");
+    for warning in &exporter.warnings {
+        let comment = warning.to_string().replace("--", "- -");
+        code_xml.push_str(&format!("<!-- warning: {comment} -->\n"));
+    }
+    code_xml.push_str(&xml.text);
+    CatrobatExport {
+        code_xml,
+        warnings: exporter.warnings,
+    }
+}
+
+fn header(xml: &mut Xml, name: &str) {
+    xml.open("header", &[]);
+    xml.leaf("applicationName", "Pocket Code");
+    xml.leaf("catrobatLanguageVersion", CATROBAT_LANGUAGE_VERSION);
+    xml.leaf("description", "Exported from BrickCreator");
+    xml.leaf("landscapeMode", "false");
+    xml.leaf("platform", "Android");
+    xml.leaf("programName", name);
+    xml.leaf("scenesEnabled", "true");
+    xml.leaf("screenHeight", "1920");
+    xml.leaf("screenMode", "STRETCH");
+    xml.leaf("screenWidth", "1080");
+    xml.close("header");
+}
+
+fn object(xml: &mut Xml, name: &str, scripts: impl FnOnce(&mut Xml)) {
+    xml.open("object", &[("type", "Sprite"), ("name", name)]);
+    xml.empty("lookList");
+    xml.empty("soundList");
+    xml.open("scriptList", &[]);
+    scripts(xml);
+    xml.close("scriptList");
+    for list in [
+        "nfcTagList",
+        "userVariables",
+        "userLists",
+        "userDefinedBrickList",
+    ] {
+        xml.empty(list);
+    }
+    xml.close("object");
+}
+
+// A variable or list by its name. Pocket Code tells them apart by their
+// device key, which is derived from the name so every use agrees.
+fn variable_element(xml: &mut Xml, tag: &str, name: &str) {
+    let kind = if tag == "userList" {
+        "UserList"
+    } else {
+        "UserVariable"
+    };
+    xml.open(tag, &[("type", kind), ("serialization", "custom")]);
+    xml.open(tag, &[]);
+    xml.open("default", &[]);
+    xml.leaf("deviceValueKey", &device_key(name));
+    xml.leaf("name", name);
+    xml.close("default");
+    xml.close(tag);
+    xml.close(tag);
+}
+
+// a UUID shaped FNV-1a hash of the name
+fn device_key(name: &str) -> String {
+    let hash = |seed: u64| {
+        name.bytes().fold(seed, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+    };
+    let (high, low) = (hash(0xcbf2_9ce4_8422_2325), hash(0x8422_2325_cbf2_9ce4));
+    format!(
+        "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+        high >> 32,
+        (high >> 16) & 0xffff,
+        high & 0xffff,
+        low >> 48,
+        low & 0xffff_ffff_ffff
+    )
+}
+
+// the catalog brick of a script brick, with the labels of its slots
+type Matched = (&'static CatalogBrick, Vec<String>, Vec<String>);
+
+fn is_hat(brick: &CatalogBrick) -> bool {
+    brick.id.ends_with("Script")
+}
+
+#[derive(Default)]
+struct Exporter {
+    warnings: Vec<ExportWarning>,
+    variables: Vec<String>,
+    lists: Vec<String>,
+}
+
+impl Exporter {
+    // The top level stack cut before every hat brick. Bricks before the first
+    // hat get a hat of their own.
+    fn split<'a>(
+        &mut self,
+        bricks: &'a [ScriptBrick],
+    ) -> Vec<(Option<Matched>, Vec<&'a ScriptBrick>)> {
+        let mut scripts: Vec<(Option<Matched>, Vec<&ScriptBrick>)> = Vec::new();
+        for brick in bricks {
+            let matched = match_catalog_brick(&brick.brick.content, brick.brick.brick_type);
+            match matched {
+                Some(matched) if is_hat(matched.0) => scripts.push((Some(matched), Vec::new())),
+                _ => {
+                    if scripts.is_empty() {
+                        self.warnings.push(ExportWarning::NoHat);
+                        scripts.push((None, Vec::new()));
+                    }
+                    if let Some((_, body)) = scripts.last_mut() {
+                        body.push(brick);
+                    }
+                }
+            }
+        }
+        scripts
+    }
+
+    fn script(&mut self, xml: &mut Xml, hat: Option<Matched>, body: &[&ScriptBrick]) {
+        let (entry, values, fields) = hat.unwrap_or_else(|| {
+            let start = find_catalog_brick(START).expect("the catalog has StartScript");
+            (start, Vec::new(), Vec::new())
+        });
+        xml.open("script", &[("type", &entry.id)]);
+        xml.open("brickList", &[]);
+        for brick in body {
+            self.brick(xml, brick);
+        }
+        xml.close("brickList");
+        xml.leaf("commentedOut", "false");
+        self.slots(xml, "formulaMap", entry, &values, &fields);
+        xml.close("script");
+    }
+
+    fn brick(&mut self, xml: &mut Xml, brick: &ScriptBrick) {
+        let matched = match_catalog_brick(&brick.brick.content, brick.brick.brick_type)
+            .filter(|(entry, _, _)| !is_hat(entry));
+        let Some((entry, values, fields)) = matched else {
+            let text: String = parse_content(&brick.brick.content)
+                .iter()
+                .map(ContentPart::label)
+                .collect();
+            self.warnings.push(ExportWarning::Unmatched(text.clone()));
+            self.note(xml, &text);
+            // nested bricks follow the note
+            for nested in brick.slots.iter().flatten() {
+                self.brick(xml, nested);
+            }
+            return;
+        };
+
+        xml.open("brick", &[("type", &entry.id)]);
+        xml.leaf("commentedOut", "false");
+        self.slots(xml, "formulaList", entry, &values, &fields);
+        let lists = slot_lists(entry.brick_type);
+        for (index, list) in lists.iter().enumerate() {
+            xml.open(list, &[]);
+            for nested in brick.slots.get(index).into_iter().flatten() {
+                self.brick(xml, nested);
+            }
+            xml.close(list);
+        }
+        xml.close("brick");
+        // slots the Catrobat brick has no list for follow it
+        for nested in brick.slots.iter().skip(lists.len()).flatten() {
+            self.brick(xml, nested);
+        }
+    }
+
+    fn note(&mut self, xml: &mut Xml, text: &str) {
+        xml.open("brick", &[("type", NOTE)]);
+        xml.leaf("commentedOut", "false");
+        xml.open("formulaList", &[]);
+        let text = Formula::Text(text.to_string());
+        formula(xml, "formula", Some("NOTE"), &text);
+        xml.close("formulaList");
+        xml.close("brick");
+    }
+
+    fn slots(
+        &mut self,
+        xml: &mut Xml,
+        formulas: &str,
+        entry: &CatalogBrick,
+        values: &[String],
+        fields: &[String],
+    ) {
+        if !entry.formulas.is_empty() {
+            xml.open(formulas, &[]);
+            for (category, value) in entry.formulas.iter().zip(values) {
+                let tree = self.formula_tree(value);
+                formula(xml, "formula", Some(category), &tree);
+            }
+            xml.close(formulas);
+        }
+        for (field, label) in entry.fields.iter().zip(fields) {
+            match field.as_str() {
+                field if UNSET_FIELDS.contains(&field) => {
+                    self.warnings.push(ExportWarning::UnsetField {
+                        brick: entry.name.clone(),
+                        field: field.to_string(),
+                    });
+                }
+                "userVariable" => {
+                    add_name(&mut self.variables, label);
+                    variable_element(xml, field, label);
+                }
+                "userList" => {
+                    add_name(&mut self.lists, label);
+                    variable_element(xml, field, label);
+                }
+                _ => xml.leaf(field, label),
+            }
+        }
+    }
+
+    // A slot as Pocket Code shows it: numbers, 'text', "variables" and
+    // operators between them. Anything else, like plain words, is text.
+    fn formula_tree(&mut self, value: &str) -> Formula {
+        let value = value.trim();
+        let is_number = value.parse::<f64>().is_ok_and(f64::is_finite)
+            && !value.contains(|c: char| c.is_alphabetic());
+        if is_number {
+            return Formula::Number(value.to_string());
+        }
+        let Some(formula) = parse_formula(value) else {
+            return Formula::Text(value.to_string());
+        };
+        let mut pending = vec![&formula];
+        while let Some(part) = pending.pop() {
+            match part {
+                Formula::Variable(name) => add_name(&mut self.variables, name),
+                Formula::Operator(_, left, right) => {
+                    pending.extend(left.as_deref());
+                    pending.push(right);
+                }
+                Formula::Bracket(inner) => pending.push(inner),
+                Formula::Number(_) | Formula::Text(_) => {}
+            }
+        }
+        formula
+    }
+}
+
+fn add_name(names: &mut Vec<String>, name: &str) {
+    if !names.iter().any(|known| known == name) {
+        names.push(name.to_string());
+    }
+}
+
+// the formula of a slot, its operands in leftChild and rightChild elements
+fn formula(xml: &mut Xml, tag: &str, category: Option<&str>, formula: &Formula) {
+    let attributes: Vec<(&str, &str)> = category
+        .map(|category| ("category", category))
+        .into_iter()
+        .collect();
+    xml.open(tag, &attributes);
+    let (kind, value) = match formula {
+        Formula::Number(number) => ("NUMBER", number.as_str()),
+        Formula::Text(text) => ("STRING", text.as_str()),
+        Formula::Variable(name) => ("USER_VARIABLE", name.as_str()),
+        Formula::Operator(operator, left, right) => {
+            if let Some(left) = left {
+                self::formula(xml, "leftChild", None, left);
+            }
+            self::formula(xml, "rightChild", None, right);
+            ("OPERATOR", *operator)
+        }
+        Formula::Bracket(inner) => {
+            self::formula(xml, "rightChild", None, inner);
+            ("BRACKET", "")
+        }
+    };
+    xml.leaf("type", kind);
+    xml.leaf("value", value);
+    xml.close(tag);
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// indented XML, two spaces per level like Pocket Code writes it
+#[derive(Default)]
+struct Xml {
+    text: String,
+    depth: usize,
+}
+
+impl Xml {
+    fn start(&mut self, tag: &str, attributes: &[(&str, &str)]) {
+        self.text.push_str(&"  ".repeat(self.depth));
+        self.text.push('<');
+        self.text.push_str(tag);
+        for (name, value) in attributes {
+            self.text
+                .push_str(&format!(" {name}=\"{}\"", escape(value)));
+        }
+    }
+
+    fn open(&mut self, tag: &str, attributes: &[(&str, &str)]) {
+        self.start(tag, attributes);
+        self.text.push_str(">\n");
+        self.depth += 1;
+    }
+
+    fn close(&mut self, tag: &str) {
+        self.depth -= 1;
+        self.text.push_str(&"  ".repeat(self.depth));
+        self.text.push_str(&format!("</{tag}>\n"));
+    }
+
+    fn empty(&mut self, tag: &str) {
+        self.start(tag, &[]);
+        self.text.push_str("/>\n");
+    }
+
+    fn leaf(&mut self, tag: &str, text: &str) {
+        self.start(tag, &[]);
+        self.text.push_str(&format!(">{}</{tag}>\n", escape(text)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catrobat::import_code_xml;
+
+    #[test]
+    fn exported_scripts_import_again() {
+        let script = Script {
+            bricks: vec![
+                find_catalog_brick("BroadcastScript").unwrap().to_spec(),
+                find_catalog_brick("SetVariableBrick").unwrap().to_spec(),
+                find_catalog_brick("IfLogicBeginBrick").unwrap().to_spec(),
+            ]
+            .into_iter()
+            .map(ScriptBrick::new)
+            .collect(),
+        };
+        let mut script = script;
+        script.bricks[1].brick.content = "Set <score> to {\"bonus\"}".to_string();
+        script.bricks[2].brick.content =
+            "If {-(\"score\" + 2) × 3 ≥ 10 and not 'a' = 'b'} is true then".to_string();
+        script.bricks[2] = script.bricks[2]
+            .clone()
+            .with_slot(vec![ScriptBrick::new(
+                find_catalog_brick("SayBubbleBrick").unwrap().to_spec(),
+            )])
+            .with_slot(Vec::new());
+
+        let export = export_catrobat(&script, "Test");
+        assert!(export.warnings.is_empty(), "{:?}", export.warnings);
+        let project = import_code_xml(&export.code_xml).unwrap();
+        assert!(project.unknown_bricks.is_empty());
+        let imported = &project.scenes[0].objects[1].scripts[0];
+        let contents = |script: &Script| -> Vec<String> {
+            script
+                .bricks
+                .iter()
+                .map(|brick| brick.brick.content.clone())
+                .collect()
+        };
+        assert_eq!(contents(imported), contents(&script));
+        assert_eq!(imported.bricks[2].slots.len(), 2);
+    }
+
+    #[test]
+    fn deeply_nested_formulas_stay_text() {
+        let set_x = |value: &str| {
+            let mut brick = find_catalog_brick("SetXBrick").unwrap().to_spec();
+            brick.content = format!("Set X to {{{value}}}");
+            Script::new(vec![ScriptBrick::new(brick)])
+        };
+        let minus = format!("{}1", "-".repeat(1_000_000));
+        let brackets = format!("{}1{}", "(".repeat(10_000), ")".repeat(10_000));
+        let sums = vec!["1"; 10_000].join(" + ");
+        for value in [&minus, &brackets, &sums] {
+            let export = export_catrobat(&set_x(value), "Test");
+            assert!(export.code_xml.contains("<type>STRING</type>"));
+            assert!(export.code_xml.len() < value.len() + 10_000);
+        }
+
+        // short formulas are still trees
+        let export = export_catrobat(&set_x("-(-(1 + 2))"), "Test");
+        assert!(!export.code_xml.contains("<type>STRING</type>"));
+        assert!(export.code_xml.contains("<type>BRACKET</type>"));
+    }
+}
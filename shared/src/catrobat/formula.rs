@@ -0,0 +1,184 @@
+// Formulas in the notation the import writes them in, e.g.
+// `"score" + 2 × (3 - 1) > 10`: numbers, 'text', "variables", the operators
+// of Pocket Code and brackets. Sensors and functions are not parsed back.
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Formula {
+    Number(String),
+    Text(String),
+    Variable(String),
+    // operator name, e.g. "PLUS", with its operands; unary ones only have
+    // the right one
+    Operator(&'static str, Option<Box<Formula>>, Box<Formula>),
+    Bracket(Box<Formula>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(String),
+    Text(String),
+    Variable(String),
+    Operator(&'static str),
+    Open,
+    Close,
+}
+
+// Longer or deeper formulas are not parsed and stay text. Parsing and writing
+// recurse once per level, and the XML indentation grows with the depth.
+const MAX_TOKENS: usize = 256;
+const MAX_DEPTH: usize = 32;
+
+// operators by their symbols, with their binding strength
+const OPERATORS: &[(&str, &str, u8)] = &[
+    ("or", "LOGICAL_OR", 0),
+    ("and", "LOGICAL_AND", 1),
+    ("=", "EQUAL", 2),
+    ("≠", "NOT_EQUAL", 2),
+    ("!=", "NOT_EQUAL", 2),
+    ("≤", "SMALLER_OR_EQUAL", 2),
+    ("<=", "SMALLER_OR_EQUAL", 2),
+    ("≥", "GREATER_OR_EQUAL", 2),
+    (">=", "GREATER_OR_EQUAL", 2),
+    ("<", "SMALLER_THAN", 2),
+    (">", "GREATER_THAN", 2),
+    ("+", "PLUS", 3),
+    ("-", "MINUS", 3),
+    ("×", "MULT", 4),
+    ("*", "MULT", 4),
+    ("÷", "DIVIDE", 4),
+    ("/", "DIVIDE", 4),
+    ("^", "POW", 5),
+    ("not", "LOGICAL_NOT", 6),
+];
+
+fn strength(operator: &str) -> Option<u8> {
+    OPERATORS
+        .iter()
+        .find(|(_, name, _)| *name == operator)
+        .map(|(_, _, strength)| *strength)
+}
+
+fn tokenize(text: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut rest = text.trim_start();
+    while let Some(c) = rest.chars().next() {
+        let (token, length) = match c {
+            '(' => (Token::Open, 1),
+            ')' => (Token::Close, 1),
+            '\'' | '"' => {
+                let end = rest[1..].find(c)? + 1;
+                let content = rest[1..end].to_string();
+                let token = if c == '"' {
+                    Token::Variable(content)
+                } else {
+                    Token::Text(content)
+                };
+                (token, end + 1)
+            }
+            '0'..='9' | '.' => {
+                let length = rest
+                    .find(|c: char| !c.is_ascii_digit() && c != '.')
+                    .unwrap_or(rest.len());
+                rest[..length].parse::<f64>().ok()?;
+                (Token::Number(rest[..length].to_string()), length)
+            }
+            _ => {
+                // words only count as a whole, "order" is no "or"
+                let (symbol, name, _) = OPERATORS.iter().find(|(symbol, _, _)| {
+                    rest.starts_with(symbol)
+                        && !(symbol.chars().all(char::is_alphabetic)
+                            && rest[symbol.len()..].starts_with(char::is_alphanumeric))
+                })?;
+                (Token::Operator(name), symbol.len())
+            }
+        };
+        tokens.push(token);
+        rest = rest[length..].trim_start();
+    }
+    Some(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    next: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next)
+    }
+
+    fn take(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.next).cloned();
+        self.next += 1;
+        token
+    }
+
+    // binary operators at least as strong as `min`, left to right
+    fn expression(&mut self, min: u8) -> Option<Formula> {
+        let mut left = self.operand()?;
+        while let Some(Token::Operator(operator)) = self.peek() {
+            let operator = *operator;
+            let strength = strength(operator)?;
+            if strength < min || operator == "LOGICAL_NOT" {
+                break;
+            }
+            self.next += 1;
+            let right = self.expression(strength + 1)?;
+            left = Formula::Operator(operator, Some(Box::new(left)), Box::new(right));
+        }
+        Some(left)
+    }
+
+    fn operand(&mut self) -> Option<Formula> {
+        match self.take()? {
+            Token::Number(number) => Some(Formula::Number(number)),
+            Token::Text(text) => Some(Formula::Text(text)),
+            Token::Variable(name) => Some(Formula::Variable(name)),
+            Token::Operator(operator @ ("MINUS" | "LOGICAL_NOT")) => {
+                let operand = self.expression(strength(operator)? + 1)?;
+                Some(Formula::Operator(operator, None, Box::new(operand)))
+            }
+            Token::Open => {
+                let inner = self.expression(0)?;
+                (self.take()? == Token::Close).then(|| Formula::Bracket(Box::new(inner)))
+            }
+            Token::Operator(_) | Token::Close => None,
+        }
+    }
+}
+
+fn tokenize_limited(text: &str) -> Option<Vec<Token>> {
+    // a cheap bound first, every token but text takes a character
+    if text.chars().filter(|c| !c.is_whitespace()).count() > MAX_TOKENS * 8 {
+        return None;
+    }
+    tokenize(text).filter(|tokens| tokens.len() <= MAX_TOKENS)
+}
+
+fn depth(formula: &Formula) -> usize {
+    let mut deepest = 0;
+    let mut pending = vec![(formula, 1)];
+    while let Some((part, depth)) = pending.pop() {
+        deepest = deepest.max(depth);
+        match part {
+            Formula::Operator(_, left, right) => {
+                pending.extend(left.as_deref().map(|left| (left, depth + 1)));
+                pending.push((right, depth + 1));
+            }
+            Formula::Bracket(inner) => pending.push((inner, depth + 1)),
+            Formula::Number(_) | Formula::Text(_) | Formula::Variable(_) => {}
+        }
+    }
+    deepest
+}
+
+// None when the text is no formula in this notation, or too long or deep
+pub fn parse_formula(text: &str) -> Option<Formula> {
+    let mut parser = Parser {
+        tokens: tokenize_limited(text)?,
+        next: 0,
+    };
+    let formula = parser.expression(0)?;
+    (parser.next == parser.tokens.len() && depth(&formula) <= MAX_DEPTH).then_some(formula)
+}
@@ -0,0 +1,598 @@
+use super::archive::read_entry;
+use super::{
+    CODE_XML, CatrobatError, CatrobatObject, CatrobatProject, CatrobatScene, ELSE_BRANCH_BRICKS,
+    IF_BRANCH_BRICKS, LOOP_BRICKS, UnknownBrick, slot_lists,
+};
+use crate::catalog::{CatalogBrick, find_catalog_brick};
+use crate::category::BrickCategory;
+use crate::color::WHITE_SCHEME;
+use crate::document::BrickSpec;
+use crate::script::{Script, ScriptBrick};
+use crate::types::BrickType;
+use roxmltree::{Document, Node};
+
+// Older projects have no nested brick lists, they put marker bricks after the
+// nested bricks instead.
+const ELSE_MARKERS: &[&str] = &["IfLogicElseBrick"];
+const END_MARKERS: &[&str] = &[
+    "LoopEndBrick",
+    "LoopEndlessBrick",
+    "IfLogicEndBrick",
+    "IfThenLogicEndBrick",
+];
+
+pub fn import_catrobat(bytes: &[u8]) -> Result<CatrobatProject, CatrobatError> {
+    let code = read_entry(bytes, CODE_XML)?.ok_or(CatrobatError::MissingCodeXml)?;
+    let code = String::from_utf8(code).map_err(|error| CatrobatError::Xml(error.to_string()))?;
+    import_code_xml(&code)
+}
+
+pub fn import_code_xml(xml: &str) -> Result<CatrobatProject, CatrobatError> {
+    let document = Document::parse(xml).map_err(|error| CatrobatError::Xml(error.to_string()))?;
+    let program = document.root_element();
+    if !program.has_tag_name("program") {
+        return Err(CatrobatError::Xml(format!(
+            "expected a <program>, found <{}>",
+            program.tag_name().name()
+        )));
+    }
+
+    let mut project = CatrobatProject {
+        name: child(program, "header")
+            .and_then(|header| child_text(header, "programName"))
+            .unwrap_or_default(),
+        ..CatrobatProject::default()
+    };
+    // projects from before scenes have their objects right in the program
+    let scenes: Vec<Node> = match child(program, "scenes") {
+        Some(scenes) => elements(scenes, "scene").collect(),
+        None => vec![program],
+    };
+    for (index, scene) in scenes.into_iter().enumerate() {
+        let mut importer = Importer {
+            scene: child_text(scene, "name").unwrap_or_else(|| format!("Scene {}", index + 1)),
+            object: String::new(),
+            script: 0,
+            unknown: &mut project.unknown_bricks,
+        };
+        let objects: Vec<Node> = child(scene, "objectList")
+            .map(|list| elements(list, "object").map(resolve).collect())
+            .unwrap_or_default();
+        let objects = objects
+            .into_iter()
+            .map(|object| importer.object(object))
+            .collect();
+        project.scenes.push(CatrobatScene {
+            name: importer.scene,
+            objects,
+        });
+    }
+    Ok(project)
+}
+
+struct Importer<'a> {
+    scene: String,
+    object: String,
+    script: usize,
+    unknown: &'a mut Vec<UnknownBrick>,
+}
+
+impl Importer<'_> {
+    fn object(&mut self, object: Node) -> CatrobatObject {
+        self.object = object
+            .attribute("name")
+            .map(str::to_string)
+            .or_else(|| child_text(object, "name"))
+            .unwrap_or_default();
+        let scripts: Vec<Node> = child(object, "scriptList")
+            .map(|list| elements(list, "script").collect())
+            .unwrap_or_default();
+        let scripts = scripts
+            .into_iter()
+            .enumerate()
+            .map(|(index, script)| {
+                self.script = index;
+                self.script_of(script)
+            })
+            .collect();
+        CatrobatObject {
+            name: self.object.clone(),
+            scripts,
+        }
+    }
+
+    // the script as a stack that starts with its hat brick
+    fn script_of(&mut self, script: Node) -> Script {
+        let script = resolve(script);
+        let mut bricks = vec![ScriptBrick::new(self.brick(script, BrickType::H1Control))];
+        let list: Vec<Node> = child(script, "brickList")
+            .map(|list| elements(list, "brick").map(resolve).collect())
+            .unwrap_or_default();
+        let legacy = list
+            .iter()
+            .any(|brick| END_MARKERS.contains(&brick_class(*brick)));
+        if legacy {
+            bricks.extend(self.legacy_stack(&list));
+        } else {
+            bricks.extend(self.stack(&list));
+        }
+        Script { bricks }
+    }
+
+    fn stack(&mut self, bricks: &[Node]) -> Vec<ScriptBrick> {
+        let mut stack = Vec::new();
+        for brick in bricks {
+            let mut script_brick = ScriptBrick::new(self.brick(*brick, BrickType::H1Base));
+            let nested = |name| {
+                child(*brick, name)
+                    .map(|list| elements(list, "brick").map(resolve).collect::<Vec<_>>())
+            };
+            let lists = slot_lists(script_brick.brick.brick_type);
+            if !lists.is_empty() {
+                for name in lists {
+                    let slot = nested(name).unwrap_or_default();
+                    script_brick = script_brick.with_slot(self.stack(&slot));
+                }
+                stack.push(script_brick);
+            } else {
+                stack.push(script_brick);
+                // nested bricks of a brick the catalog does not know follow it
+                for name in [IF_BRANCH_BRICKS, ELSE_BRANCH_BRICKS, LOOP_BRICKS] {
+                    if let Some(slot) = nested(name) {
+                        stack.extend(self.stack(&slot));
+                    }
+                }
+            }
+        }
+        stack
+    }
+
+    // One flat list with the end of every C-shaped brick marked by a brick of
+    // its own. Open bricks wait on a stack until their end marker, unclosed
+    // ones are closed at the end of the script.
+    fn legacy_stack(&mut self, bricks: &[Node]) -> Vec<ScriptBrick> {
+        let mut open: Vec<ScriptBrick> = Vec::new();
+        let mut top = Vec::new();
+        let close = |brick: ScriptBrick,
+                     open: &mut Vec<ScriptBrick>,
+                     top: &mut Vec<ScriptBrick>| {
+            match open.last_mut().and_then(|parent| parent.slots.last_mut()) {
+                Some(slot) => slot.push(brick),
+                None => top.push(brick),
+            }
+        };
+        for brick in bricks {
+            let class = brick_class(*brick);
+            if ELSE_MARKERS.contains(&class) {
+                if let Some(parent) = open.last_mut() {
+                    parent.slots.push(Vec::new());
+                }
+                continue;
+            }
+            if END_MARKERS.contains(&class) {
+                if let Some(finished) = open.pop() {
+                    close(finished, &mut open, &mut top);
+                }
+                continue;
+            }
+            let script_brick = ScriptBrick::new(self.brick(*brick, BrickType::H1Base));
+            if slot_lists(script_brick.brick.brick_type).is_empty() {
+                close(script_brick, &mut open, &mut top);
+            } else {
+                open.push(script_brick.with_slot(Vec::new()));
+            }
+        }
+        while let Some(finished) = open.pop() {
+            close(finished, &mut open, &mut top);
+        }
+        top
+    }
+
+    // The catalog brick of the element with its slots filled in, or a
+    // placeholder of the given shape that names the unknown class.
+    fn brick(&mut self, node: Node, placeholder: BrickType) -> BrickSpec {
+        let class = brick_class(node);
+        match find_catalog_brick(class) {
+            Some(entry) => BrickSpec::new(
+                entry.brick_type,
+                entry.category.color_scheme(),
+                &fill_content(entry, node),
+            ),
+            None => {
+                self.unknown.push(UnknownBrick {
+                    brick_type: class.to_string(),
+                    scene: self.scene.clone(),
+                    object: self.object.clone(),
+                    script: self.script,
+                });
+                let content = if class.is_empty() {
+                    "Unknown brick"
+                } else {
+                    class
+                };
+                let scheme = if placeholder == BrickType::H1Control {
+                    BrickCategory::Event.color_scheme()
+                } else {
+                    WHITE_SCHEME
+                };
+                BrickSpec::new(placeholder, scheme, content)
+            }
+        }
+    }
+}
+
+fn fill_content(entry: &CatalogBrick, node: Node) -> String {
+    let values: Vec<Option<String>> = entry
+        .formulas
+        .iter()
+        .map(|category| formula(node, category).map(formula_text))
+        .collect();
+    let fields: Vec<Option<String>> = entry
+        .fields
+        .iter()
+        .map(|name| child(node, name).and_then(field_text))
+        .collect();
+    entry.content_with(&values, &fields)
+}
+
+// the class of a brick or script, in the `type` attribute since format 0.99
+fn brick_class<'a>(node: Node<'a, '_>) -> &'a str {
+    node.attribute("type")
+        .or_else(|| node.attribute("class"))
+        .unwrap_or_else(|| node.tag_name().name())
+}
+
+fn elements<'a, 'input>(
+    node: Node<'a, 'input>,
+    name: &'static str,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    node.children()
+        .filter(move |child| child.has_tag_name(name))
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children()
+        .find(|child| child.has_tag_name(name))
+        .map(resolve)
+}
+
+fn child_text(node: Node, name: &str) -> Option<String> {
+    child(node, name)
+        .and_then(|child| child.text())
+        .map(|text| text.trim().to_string())
+}
+
+// XStream writes an object only once and points to it from every later use
+// with a path relative to the referring element, e.g. "../../object[2]".
+fn resolve<'a, 'input>(node: Node<'a, 'input>) -> Node<'a, 'input> {
+    let Some(path) = node.attribute("reference") else {
+        return node;
+    };
+    let mut target = node;
+    for step in path.split('/') {
+        let next = match step {
+            "" | "." => Some(target),
+            ".." => target.parent_element(),
+            _ => {
+                let (name, index) = match step.split_once('[') {
+                    Some((name, index)) => (
+                        name,
+                        index.trim_end_matches(']').parse::<usize>().unwrap_or(1),
+                    ),
+                    None => (step, 1),
+                };
+                target
+                    .children()
+                    .filter(|child| child.has_tag_name(name))
+                    .nth(index.saturating_sub(1))
+            }
+        };
+        let Some(next) = next else {
+            return node;
+        };
+        target = next;
+    }
+    target
+}
+
+// the formula of the category in the formula list of the brick
+fn formula<'a, 'input>(brick: Node<'a, 'input>, category: &str) -> Option<Node<'a, 'input>> {
+    brick
+        .children()
+        .filter(|list| list.has_tag_name("formulaList") || list.has_tag_name("formulaMap"))
+        .flat_map(|list| list.children())
+        .find(|formula| formula.attribute("category") == Some(category))
+}
+
+// the name of a look, sound, variable or object, or the text of a message
+fn field_text(node: Node) -> Option<String> {
+    let text = node
+        .attribute("name")
+        .map(str::to_string)
+        .or_else(|| {
+            node.descendants()
+                .find(|descendant| descendant.has_tag_name("name"))
+                .and_then(|name| name.text())
+                .map(str::to_string)
+        })
+        .or_else(|| node.text().map(str::to_string))?;
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+// A formula as Pocket Code shows it: strings in single quotes, variables in
+// double quotes and sensors and functions by their lowercase names.
+fn formula_text(node: Node) -> String {
+    let value = child_text(node, "value").unwrap_or_default();
+    let left = child(node, "leftChild").map(formula_text);
+    let right = child(node, "rightChild").map(formula_text);
+    let name = || value.to_lowercase().replace('_', " ");
+    match child_text(node, "type").as_deref() {
+        Some("STRING") => format!("'{value}'"),
+        Some("USER_VARIABLE" | "USER_LIST" | "USER_DEFINED_BRICK_INPUT") => format!("\"{value}\""),
+        Some("SENSOR") => name(),
+        Some("BRACKET") => format!("({})", right.unwrap_or_default()),
+        Some("FUNCTION") => {
+            let arguments: Vec<String> = left.into_iter().chain(right).collect();
+            format!("{}({})", name(), arguments.join(", "))
+        }
+        Some("OPERATOR") => {
+            let operator = match value.as_str() {
+                "PLUS" => "+",
+                "MINUS" => "-",
+                "MULT" => "×",
+                "DIVIDE" => "÷",
+                "POW" => "^",
+                "EQUAL" => "=",
+                "NOT_EQUAL" => "≠",
+                "SMALLER_THAN" => "<",
+                "SMALLER_OR_EQUAL" => "≤",
+                "GREATER_THAN" => ">",
+                "GREATER_OR_EQUAL" => "≥",
+                "LOGICAL_AND" => "and",
+                "LOGICAL_OR" => "or",
+                "LOGICAL_NOT" => "not",
+                _ => value.as_str(),
+            };
+            match (left, right) {
+                (Some(left), Some(right)) => format!("{left} {operator} {right}"),
+                // unary minus and not
+                (None, Some(operand)) | (Some(operand), None) => {
+                    if operator.chars().all(char::is_alphabetic) {
+                        format!("{operator} {operand}")
+                    } else {
+                        format!("{operator}{operand}")
+                    }
+                }
+                (None, None) => operator.to_string(),
+            }
+        }
+        _ => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn first_script(xml: &str) -> (Script, Vec<UnknownBrick>) {
+        let project = import_code_xml(xml).unwrap();
+        let script = project.scenes[0].objects[0].scripts[0].clone();
+        (script, project.unknown_bricks)
+    }
+
+    #[test]
+    fn nested_and_legacy_bricks_import_alike() {
+        let nested = r#"<program><scenes><scene><name>S</name><objectList><object name="O"><scriptList>
+            <script type="StartScript"><brickList>
+              <brick type="RepeatBrick">
+                <formulaList><formula category="TIMES_TO_REPEAT"><type>NUMBER</type><value>3</value></formula></formulaList>
+                <loopBricks><brick type="HideBrick"/><brick type="NewBrick"/></loopBricks>
+              </brick>
+            </brickList></script>
+          </scriptList></object></objectList></scene></scenes></program>"#;
+        let legacy = r#"<program><objectList><object name="O"><scriptList>
+            <script type="StartScript"><brickList>
+              <brick type="RepeatBrick"><formulaList><formula category="TIMES_TO_REPEAT"><type>NUMBER</type><value>3</value></formula></formulaList></brick>
+              <brick type="HideBrick"/><brick type="NewBrick"/>
+              <brick type="LoopEndBrick"/>
+            </brickList></script>
+          </scriptList></object></objectList></program>"#;
+
+        let (nested, unknown) = first_script(nested);
+        assert_eq!(nested, first_script(legacy).0);
+        assert_eq!(nested.bricks.len(), 2);
+        assert_eq!(nested.bricks[1].brick.content, "Repeat {3} times");
+        assert_eq!(nested.bricks[1].slots[0].len(), 2);
+        assert_eq!(unknown.len(), 1);
+        assert_eq!(unknown[0].brick_type, "NewBrick");
+    }
+
+    // the content of a <formula>, its children are written the same way
+    fn formula_of(content: &str) -> String {
+        let xml = format!("<formula>{content}</formula>");
+        let document = Document::parse(&xml).unwrap();
+        formula_text(document.root_element())
+    }
+
+    fn leaf(kind: &str, value: &str) -> String {
+        format!("<type>{kind}</type><value>{value}</value>")
+    }
+
+    fn operator(name: &str, left: Option<&str>, right: &str) -> String {
+        let left = left.map_or_else(String::new, |left| format!("<leftChild>{left}</leftChild>"));
+        format!(
+            "{left}<rightChild>{right}</rightChild>{}",
+            leaf("OPERATOR", name)
+        )
+    }
+
+    #[test]
+    fn references_point_to_earlier_elements() {
+        let xml = r#"<program><scenes><scene><name>S</name><objectList>
+            <object name="Cat">
+              <lookList><look fileName="a.png" name="Sitting"/><look fileName="b.png" name="Jumping"/></lookList>
+              <scriptList><script type="StartScript"><brickList>
+                <brick type="SetLookBrick"><look reference="../../../../../lookList/look[2]"/></brick>
+                <brick type="SetLookBrick"><look reference="../../../../../lookList/look"/></brick>
+                <brick type="SetLookBrick"><look reference="../../nothing[3]"/></brick>
+              </brickList></script></scriptList>
+            </object>
+            <object reference="../object"/>
+          </objectList></scene></scenes></program>"#;
+        let project = import_code_xml(xml).unwrap();
+        let objects = &project.scenes[0].objects;
+        let contents: Vec<&str> = objects[0].scripts[0].bricks[1..]
+            .iter()
+            .map(|brick| brick.brick.content.as_str())
+            .collect();
+        assert_eq!(
+            contents,
+            [
+                "Switch to look [Jumping]",
+                "Switch to look [Sitting]",
+                // a broken reference keeps the default of the catalog
+                "Switch to look [look 1]",
+            ]
+        );
+        assert_eq!(objects[1].name, "Cat");
+        assert_eq!(objects[1].scripts, objects[0].scripts);
+    }
+
+    #[test]
+    fn formulas_read_like_pocket_code() {
+        let number = |value: &str| leaf("NUMBER", value);
+        let cases = [
+            ("PLUS", "+"),
+            ("MINUS", "-"),
+            ("MULT", "×"),
+            ("DIVIDE", "÷"),
+            ("POW", "^"),
+            ("EQUAL", "="),
+            ("NOT_EQUAL", "≠"),
+            ("SMALLER_THAN", "<"),
+            ("SMALLER_OR_EQUAL", "≤"),
+            ("GREATER_THAN", ">"),
+            ("GREATER_OR_EQUAL", "≥"),
+            ("LOGICAL_AND", "and"),
+            ("LOGICAL_OR", "or"),
+        ];
+        for (name, symbol) in cases {
+            let xml = operator(name, Some(&number("1")), &number("2"));
+            assert_eq!(formula_of(&xml), format!("1 {symbol} 2"));
+        }
+
+        assert_eq!(formula_of(&operator("MINUS", None, &number("5"))), "-5");
+        let variable = leaf("USER_VARIABLE", "score");
+        assert_eq!(
+            formula_of(&operator("LOGICAL_NOT", None, &variable)),
+            "not \"score\""
+        );
+        assert_eq!(formula_of(&leaf("STRING", "hi")), "'hi'");
+        assert_eq!(formula_of(&leaf("SENSOR", "OBJECT_X")), "object x");
+
+        let sum = operator("PLUS", Some(&variable), &number("1"));
+        let bracket = format!("<rightChild>{sum}</rightChild>{}", leaf("BRACKET", ""));
+        let product = operator("MULT", Some(&bracket), &number("2"));
+        assert_eq!(formula_of(&product), "(\"score\" + 1) × 2");
+
+        let function = format!(
+            "<leftChild>{}</leftChild><rightChild>{}</rightChild>{}",
+            number("1"),
+            number("6"),
+            leaf("FUNCTION", "RAND")
+        );
+        assert_eq!(formula_of(&function), "rand(1, 6)");
+    }
+
+    #[test]
+    fn unknown_scripts_become_event_placeholders() {
+        let xml = r#"<program><scenes><scene><name>S</name><objectList><object name="O"><scriptList>
+            <script type="StartScript"><brickList/></script>
+            <script type="WhenMagicScript"><brickList><brick type="ShowBrick"/></brickList></script>
+          </scriptList></object></objectList></scene></scenes></program>"#;
+        let project = import_code_xml(xml).unwrap();
+        let script = &project.scenes[0].objects[0].scripts[1];
+        let hat = &script.bricks[0].brick;
+        assert_eq!(hat.brick_type, BrickType::H1Control);
+        assert_eq!(hat.color_scheme, BrickCategory::Event.color_scheme());
+        assert_eq!(hat.content, "WhenMagicScript");
+        assert_eq!(script.bricks[1].brick.content, "Show");
+        assert_eq!(
+            project.unknown_bricks,
+            [UnknownBrick {
+                brick_type: "WhenMagicScript".to_string(),
+                scene: "S".to_string(),
+                object: "O".to_string(),
+                script: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn programs_without_scenes_have_one() {
+        let xml = r#"<program><header><programName>Old</programName></header><objectList>
+            <object name="Background"><scriptList/></object>
+            <object><name>Cat</name><scriptList><script type="WhenScript"><brickList/></script></scriptList></object>
+          </objectList></program>"#;
+        let project = import_code_xml(xml).unwrap();
+        assert_eq!(project.name, "Old");
+        assert_eq!(project.scenes.len(), 1);
+        assert_eq!(project.scenes[0].name, "Scene 1");
+        let names: Vec<&str> = project.scenes[0]
+            .objects
+            .iter()
+            .map(|object| object.name.as_str())
+            .collect();
+        assert_eq!(names, ["Background", "Cat"]);
+        assert_eq!(
+            project.scenes[0].objects[1].scripts[0].bricks[0]
+                .brick
+                .content,
+            "When tapped"
+        );
+    }
+
+    #[test]
+    fn legacy_markers_close_if_else_bricks() {
+        let xml = r#"<program><objectList><object name="O"><scriptList>
+            <script type="StartScript"><brickList>
+              <brick type="IfLogicBeginBrick"><formulaList><formula category="IF_CONDITION"><type>NUMBER</type><value>1</value></formula></formulaList></brick>
+              <brick type="ShowBrick"/>
+              <brick type="RepeatBrick"/>
+              <brick type="HideBrick"/>
+              <brick type="LoopEndBrick"/>
+              <brick type="IfLogicElseBrick"/>
+              <brick type="HideBrick"/>
+              <brick type="IfLogicEndBrick"/>
+              <brick type="WaitBrick"/>
+              <brick type="IfThenLogicBeginBrick"/>
+              <brick type="ShowBrick"/>
+            </brickList></script>
+          </scriptList></object></objectList></program>"#;
+        let (script, unknown) = first_script(xml);
+        assert!(unknown.is_empty());
+        let contents = |bricks: &[ScriptBrick]| -> Vec<String> {
+            bricks
+                .iter()
+                .map(|brick| brick.brick.content.clone())
+                .collect()
+        };
+        assert_eq!(
+            contents(&script.bricks),
+            [
+                "When scene starts",
+                "If {1} is true then",
+                "Wait {1} second",
+                "If {condition} is true then"
+            ]
+        );
+        let if_else = &script.bricks[1];
+        assert_eq!(if_else.brick.brick_type, BrickType::IfElse);
+        assert_eq!(contents(&if_else.slots[0]), ["Show", "Repeat {10} times"]);
+        assert_eq!(contents(&if_else.slots[0][1].slots[0]), ["Hide"]);
+        assert_eq!(contents(&if_else.slots[1]), ["Hide"]);
+        // a brick without its end marker is closed at the end of the script
+        assert_eq!(contents(&script.bricks[3].slots[0]), ["Show"]);
+    }
+}
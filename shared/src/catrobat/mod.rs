@@ -0,0 +1,103 @@
+use crate::script::Script;
+use crate::types::BrickType;
+use std::fmt;
+
+// Catrobat projects as Pocket Code saves them: a ZIP archive with the
+// program in code.xml next to the images and sounds. The program holds
+// scenes, the scenes hold objects and every object has its scripts.
+//
+// Imported bricks take their shape, colors and content from the catalog.
+// Bricks the catalog does not know are kept as placeholders and listed in
+// `CatrobatProject::unknown_bricks`, so one new brick in Pocket Code does not
+// break the import of a whole project.
+//
+// Exports go the other way: script bricks are matched to the catalog by
+// their text, and what has no Catrobat brick becomes a note.
+
+pub mod archive;
+mod export;
+mod formula;
+mod import;
+
+pub use export::{CATROBAT_LANGUAGE_VERSION, CatrobatExport, ExportWarning, export_catrobat};
+pub use import::{import_catrobat, import_code_xml};
+
+pub const CODE_XML: &str = "code.xml";
+
+// the lists that hold the nested bricks of C-shaped bricks
+const LOOP_BRICKS: &str = "loopBricks";
+const IF_BRANCH_BRICKS: &str = "ifBranchBricks";
+const ELSE_BRANCH_BRICKS: &str = "elseBranchBricks";
+
+// the lists behind the slots of a C-shaped brick, in slot order
+const fn slot_lists(brick_type: BrickType) -> &'static [&'static str] {
+    match brick_type {
+        BrickType::If => &[IF_BRANCH_BRICKS],
+        BrickType::IfElse => &[IF_BRANCH_BRICKS, ELSE_BRANCH_BRICKS],
+        BrickType::Repeat | BrickType::Forever => &[LOOP_BRICKS],
+        _ => &[],
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CatrobatError {
+    Archive(String),
+    Xml(String),
+    MissingCodeXml,
+}
+
+impl fmt::Display for CatrobatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Archive(message) => write!(f, "could not read Catrobat archive: {message}"),
+            Self::Xml(message) => write!(f, "could not read {CODE_XML}: {message}"),
+            Self::MissingCodeXml => write!(f, "the archive has no {CODE_XML}"),
+        }
+    }
+}
+
+impl std::error::Error for CatrobatError {}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CatrobatProject {
+    pub name: String,
+    pub scenes: Vec<CatrobatScene>,
+    pub unknown_bricks: Vec<UnknownBrick>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CatrobatScene {
+    pub name: String,
+    pub objects: Vec<CatrobatObject>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CatrobatObject {
+    pub name: String,
+    // one script per Catrobat script, starting with its hat brick
+    pub scripts: Vec<Script>,
+}
+
+// a brick or script of code.xml without an entry in the catalog
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownBrick {
+    // the Catrobat class name, e.g. "PhiroMotorMoveForwardBrick"
+    pub brick_type: String,
+    pub scene: String,
+    pub object: String,
+    // index of the script in the object
+    pub script: usize,
+}
+
+impl fmt::Display for UnknownBrick {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown brick {} in script {} of '{}' in scene '{}'",
+            self.brick_type,
+            self.script + 1,
+            self.object,
+            self.scene
+        )
+    }
+}
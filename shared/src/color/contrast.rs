@@ -0,0 +1,151 @@
+use super::{ALL_COLOR_SCHEMES, Color, ColorScheme};
+use std::fmt;
+
+// WCAG contrast checks of the text of a scheme against its body color.
+//
+// Bricks render at one pixel per SVG unit by default, so their 12 unit labels
+// are normal sized text and schemes need the AA ratio of 4.5:1.
+
+pub const AA_LARGE_CONTRAST: f32 = 3.0;
+pub const AA_CONTRAST: f32 = 4.5;
+pub const AAA_CONTRAST: f32 = 7.0;
+// the ratio every built-in scheme has to reach
+pub const MIN_TEXT_CONTRAST: f32 = AA_CONTRAST;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ContrastLevel {
+    Fail,
+    AaLarge,
+    Aa,
+    Aaa,
+}
+
+impl ContrastLevel {
+    pub fn of(ratio: f32) -> Self {
+        if ratio >= AAA_CONTRAST {
+            Self::Aaa
+        } else if ratio >= AA_CONTRAST {
+            Self::Aa
+        } else if ratio >= AA_LARGE_CONTRAST {
+            Self::AaLarge
+        } else {
+            Self::Fail
+        }
+    }
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Fail => "Fail",
+            Self::AaLarge => "AA large",
+            Self::Aa => "AA",
+            Self::Aaa => "AAA",
+        }
+    }
+}
+
+// the text of a scheme that is too hard to read
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContrastWarning {
+    pub scheme: String,
+    pub ratio: f32,
+}
+
+impl fmt::Display for ContrastWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the text of color scheme '{}' has a contrast of {:.2}:1, below {MIN_TEXT_CONTRAST}:1",
+            self.scheme, self.ratio
+        )
+    }
+}
+
+pub(super) fn to_linear(channel: u8) -> f32 {
+    let value = f32::from(channel) / 255.0;
+    if value <= 0.040_45 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+impl Color {
+    // relative luminance as defined by WCAG, ignoring the alpha channel
+    pub fn luminance(self) -> f32 {
+        0.2126f32.mul_add(
+            to_linear(self.r),
+            0.7152f32.mul_add(to_linear(self.g), 0.0722 * to_linear(self.b)),
+        )
+    }
+
+    // this color painted over an opaque backdrop
+    #[must_use]
+    pub fn over(self, backdrop: Self) -> Self {
+        let alpha = self.opacity();
+        let mix = |top: u8, bottom: u8| {
+            f32::from(top)
+                .mul_add(alpha, f32::from(bottom) * (1.0 - alpha))
+                .round() as u8
+        };
+        Self::rgb(
+            mix(self.r, backdrop.r),
+            mix(self.g, backdrop.g),
+            mix(self.b, backdrop.b),
+        )
+    }
+}
+
+// WCAG contrast ratio of two opaque colors, from 1 to 21
+pub fn contrast(first: Color, second: Color) -> f32 {
+    let (first, second) = (first.luminance(), second.luminance());
+    (first.max(second) + 0.05) / (first.min(second) + 0.05)
+}
+
+// Contrast of the text on the body of the scheme. Transparent bodies are
+// checked on white and on black, whichever reads worse.
+pub fn text_contrast(scheme: &ColorScheme) -> f32 {
+    [Color::WHITE, Color::BLACK]
+        .into_iter()
+        .map(|backdrop| {
+            let body = scheme.color.over(backdrop);
+            contrast(scheme.text.over(body), body)
+        })
+        .fold(f32::INFINITY, f32::min)
+}
+
+pub fn contrast_level(scheme: &ColorScheme) -> ContrastLevel {
+    ContrastLevel::of(text_contrast(scheme))
+}
+
+pub fn check_scheme(scheme: &ColorScheme) -> Option<ContrastWarning> {
+    let ratio = text_contrast(scheme);
+    (ratio < MIN_TEXT_CONTRAST).then(|| ContrastWarning {
+        scheme: scheme.name.to_string(),
+        ratio,
+    })
+}
+
+// warnings for the built-in schemes and the given custom ones
+pub fn check_schemes(custom: &[ColorScheme]) -> Vec<ContrastWarning> {
+    ALL_COLOR_SCHEMES
+        .iter()
+        .chain(custom)
+        .filter_map(check_scheme)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_schemes_reach_aa() {
+        let warnings: Vec<String> = ALL_COLOR_SCHEMES
+            .iter()
+            .map(|scheme| (scheme, text_contrast(scheme)))
+            .filter(|(_, ratio)| *ratio < AA_CONTRAST)
+            .map(|(scheme, ratio)| format!("{}: {ratio:.2}:1", scheme.name))
+            .collect();
+        assert!(warnings.is_empty(), "{}", warnings.join("\n"));
+    }
+}
@@ -0,0 +1,214 @@
+use super::Color;
+use super::contrast::{MIN_TEXT_CONTRAST, contrast, to_linear};
+
+// Conversions between sRGB and OKLCH, and the colors a custom scheme
+// derives from its base color. The derivation works in OKLCH,
+// where equal steps in lightness look equal for every hue, so derived shades
+// of different colors match each other.
+
+// lightness and chroma of a shade relative to its base color, the averages of
+// the hand-picked Catrobat shades
+const SHADE_LIGHTNESS: f32 = 0.75;
+const SHADE_CHROMA: f32 = 0.7;
+// the built-in border #383838 has this lightness, derived borders keep a hint
+// of the hue and stay darker than their base color
+const BORDER_LIGHTNESS: f32 = 0.34;
+const BORDER_CHROMA: f32 = 0.02;
+
+// lightness from 0 to 1, chroma from 0 to about 0.4 and hue in degrees
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Oklch {
+    pub l: f32,
+    pub c: f32,
+    pub h: f32,
+}
+
+fn from_linear(value: f32) -> f32 {
+    if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055f32.mul_add(value.powf(1.0 / 2.4), -0.055)
+    }
+}
+
+fn to_channel(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+impl From<Color> for Oklch {
+    fn from(color: Color) -> Self {
+        let [r, g, b] = [color.r, color.g, color.b].map(to_linear);
+        let l = 0.051_445_995f32
+            .mul_add(b, 0.412_221_46f32.mul_add(r, 0.536_332_55 * g))
+            .cbrt();
+        let m = 0.107_396_96f32
+            .mul_add(b, 0.211_903_5f32.mul_add(r, 0.680_699_5 * g))
+            .cbrt();
+        let s = 0.629_978_7f32
+            .mul_add(b, 0.088_302_46f32.mul_add(r, 0.281_718_85 * g))
+            .cbrt();
+        let lightness = (-0.004_072_047f32).mul_add(s, 0.210_454_26f32.mul_add(l, 0.793_617_8 * m));
+        let a = 0.450_593_7f32.mul_add(s, 1.977_998_5f32.mul_add(l, -2.428_592_2 * m));
+        let b = (-0.808_675_77f32).mul_add(s, 0.025_904_037f32.mul_add(l, 0.782_771_77 * m));
+        Self {
+            l: lightness,
+            c: a.hypot(b),
+            h: b.atan2(a).to_degrees().rem_euclid(360.0),
+        }
+    }
+}
+
+impl Oklch {
+    // linear sRGB, outside of 0 to 1 when the color is out of gamut
+    fn to_linear_rgb(self) -> [f32; 3] {
+        let (sin, cos) = self.h.to_radians().sin_cos();
+        let (a, b) = (self.c * cos, self.c * sin);
+        let l = 0.215_803_76f32
+            .mul_add(b, 0.396_337_78f32.mul_add(a, self.l))
+            .powi(3);
+        let m = (-0.063_854_17f32)
+            .mul_add(b, (-0.105_561_346f32).mul_add(a, self.l))
+            .powi(3);
+        let s = (-1.291_485_5f32)
+            .mul_add(b, (-0.089_484_18f32).mul_add(a, self.l))
+            .powi(3);
+        [
+            0.230_969_94f32.mul_add(s, 4.076_741_7f32.mul_add(l, -3.307_711_6 * m)),
+            (-0.341_319_38f32).mul_add(s, (-1.268_438f32).mul_add(l, 2.609_757_4 * m)),
+            1.707_614_7f32.mul_add(s, (-0.004_196_086_3f32).mul_add(l, -0.703_418_6 * m)),
+        ]
+    }
+
+    // Colors outside of sRGB keep their lightness and hue and lose as much
+    // chroma as needed to fit.
+    pub fn to_color(self) -> Color {
+        let in_gamut = |color: Self| {
+            color
+                .to_linear_rgb()
+                .iter()
+                .all(|channel| (-0.000_1..=1.000_1).contains(channel))
+        };
+        let mut color = self;
+        if !in_gamut(color) {
+            let (mut low, mut high) = (0.0, self.c);
+            for _ in 0..16 {
+                color.c = (low + high) / 2.0;
+                if in_gamut(color) {
+                    low = color.c;
+                } else {
+                    high = color.c;
+                }
+            }
+            color.c = low;
+        }
+        let [r, g, b] = color
+            .to_linear_rgb()
+            .map(|channel| to_channel(from_linear(channel)));
+        Color::rgb(r, g, b)
+    }
+}
+
+// keeps the alpha channel, so shades of transparent colors stay transparent
+pub fn derive_shade(color: Color) -> Color {
+    let base = Oklch::from(color);
+    Oklch {
+        l: base.l * SHADE_LIGHTNESS,
+        c: base.c * SHADE_CHROMA,
+        h: base.h,
+    }
+    .to_color()
+    .with_alpha(color.a)
+}
+
+pub fn derive_border(color: Color) -> Color {
+    let base = Oklch::from(color);
+    Oklch {
+        l: BORDER_LIGHTNESS.min(base.l * 0.8),
+        c: base.c.min(BORDER_CHROMA),
+        h: base.h,
+    }
+    .to_color()
+}
+
+// White like in the built-in schemes, unless it falls below the contrast the
+// schemes need. Black is readable on every lighter color.
+pub fn derive_text(color: Color) -> Color {
+    if contrast(color, Color::WHITE) >= MIN_TEXT_CONTRAST {
+        Color::WHITE
+    } else {
+        Color::BLACK
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::contrast::AA_CONTRAST;
+
+    fn assert_oklch(color: Color, l: f32, c: f32, h: Option<f32>) {
+        let oklch = Oklch::from(color);
+        assert!((oklch.l - l).abs() < 1e-3, "{color}: {oklch:?}");
+        assert!((oklch.c - c).abs() < 1e-3, "{color}: {oklch:?}");
+        if let Some(h) = h {
+            assert!((oklch.h - h).abs() < 0.1, "{color}: {oklch:?}");
+        }
+    }
+
+    // every channel in steps of 15, from 0 to 255
+    fn color_sweep() -> impl Iterator<Item = Color> {
+        let steps = || (0..=255).step_by(15);
+        steps()
+            .flat_map(move |r| steps().flat_map(move |g| steps().map(move |b| Color::rgb(r, g, b))))
+    }
+
+    #[test]
+    fn known_colors_convert() {
+        assert_oklch(Color::WHITE, 1.0, 0.0, None);
+        assert_oklch(Color::BLACK, 0.0, 0.0, None);
+        assert_oklch(Color::rgb(255, 0, 0), 0.627_955, 0.257_683, Some(29.234));
+        assert_oklch(Color::rgb(0, 0, 255), 0.452_014, 0.313_214, Some(264.052));
+        assert_eq!(
+            Oklch {
+                l: 1.0,
+                c: 0.0,
+                h: 0.0
+            }
+            .to_color(),
+            Color::WHITE
+        );
+    }
+
+    #[test]
+    fn colors_round_trip() {
+        for color in color_sweep() {
+            let back = Oklch::from(color).to_color();
+            let close = [(color.r, back.r), (color.g, back.g), (color.b, back.b)]
+                .iter()
+                .all(|(channel, back)| channel.abs_diff(*back) <= 1);
+            assert!(close, "{color} came back as {back}");
+        }
+    }
+
+    #[test]
+    fn out_of_gamut_colors_lose_chroma() {
+        let vivid = Oklch {
+            l: 0.7,
+            c: 0.4,
+            h: 150.0,
+        };
+        let fitted = Oklch::from(vivid.to_color());
+        assert!((fitted.l - vivid.l).abs() < 0.01, "{fitted:?}");
+        assert!((fitted.h - vivid.h).abs() < 1.0, "{fitted:?}");
+        assert!(fitted.c < vivid.c);
+    }
+
+    #[test]
+    fn derived_text_is_readable() {
+        for color in color_sweep() {
+            let text = derive_text(color);
+            assert!(contrast(color, text) >= AA_CONTRAST, "{text} on {color}");
+        }
+        assert_eq!(derive_text(Color::rgb(0, 0, 128)), Color::WHITE);
+        assert_eq!(derive_text(Color::rgb(255, 255, 0)), Color::BLACK);
+    }
+}
@@ -0,0 +1,303 @@
+pub mod contrast;
+pub mod convert;
+pub mod rgba;
+
+pub use rgba::{Color, ParseColorError};
+
+use convert::{derive_border, derive_shade, derive_text};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Cow;
+
+// Default color constants
+pub const BLUE: Color = Color::hex("#408ac5");
+pub const BLUE_SHADE: Color = Color::hex("#27567c");
+pub const CYAN: Color = Color::hex("#26a6ae");
+pub const CYAN_SHADE: Color = Color::hex("#2e7078");
+pub const DARK_BLUE: Color = Color::hex("#395cab");
+pub const DARK_BLUE_SHADE: Color = Color::hex("#889dcd");
+pub const GOLD: Color = Color::hex("#95750c");
+pub const GOLD_SHADE: Color = Color::hex("#57452c");
+pub const DARK_GREEN: Color = Color::hex("#305716");
+pub const DARK_GREEN_SHADE: Color = Color::hex("#173718");
+pub const GREEN: Color = Color::hex("#6b9c49");
+pub const GREEN_SHADE: Color = Color::hex("#486822");
+pub const LIGHT_ORANGE: Color = Color::hex("#f99761");
+pub const LIGHT_ORANGE_SHADE: Color = Color::hex("#a86d45");
+pub const OLIVE: Color = Color::hex("#aea626");
+pub const OLIVE_SHADE: Color = Color::hex("#7e7a30");
+pub const ORANGE: Color = Color::hex("#cf5717");
+pub const ORANGE_SHADE: Color = Color::hex("#7a3a18");
+pub const YELLOW: Color = Color::hex("#fccb41");
+pub const YELLOW_SHADE: Color = Color::hex("#aa8832");
+pub const VIOLET: Color = Color::hex("#8f4cba");
+pub const VIOLET_SHADE: Color = Color::hex("#5d2d7c");
+pub const PINK: Color = Color::hex("#cf7aa6");
+pub const PINK_SHADE: Color = Color::hex("#935e7b");
+pub const RED: Color = Color::hex("#f24e50");
+pub const RED_SHADE: Color = Color::hex("#ae2f2f");
+pub const WINERED: Color = Color::hex("#910d06");
+pub const WINERED_SHADE: Color = Color::hex("#750701");
+pub const WHITE: Color = Color::hex("#ffffff");
+pub const WHITE_SHADE: Color = Color::hex("#a9b4cd");
+pub const WHITE_BORDER: Color = Color::hex("#274383");
+pub const DEFAULT_BORDER: Color = Color::hex("#383838");
+
+// Transparent colors (#rrggbbaa, the alpha channel last): a half transparent
+// body, a lighter shade and a border that stays visible. The black body is a
+// bit denser so white text keeps AA contrast on a white page.
+pub const TRANSPARENT_WHITE: Color = Color::hex("#ffffff80");
+pub const TRANSPARENT_WHITE_SHADE: Color = Color::hex("#ffffff40");
+pub const TRANSPARENT_WHITE_BORDER: Color = Color::hex("#ffffffcc");
+pub const TRANSPARENT_BLACK: Color = Color::hex("#00000099");
+pub const TRANSPARENT_BLACK_SHADE: Color = Color::hex("#00000040");
+pub const TRANSPARENT_BLACK_BORDER: Color = Color::hex("#000000cc");
+
+// Text colors. Light bodies take black text, see `contrast`.
+pub const BLACK_TEXT: Color = Color::hex("#000000");
+pub const BLUE_TEXT: Color = Color::hex("#0000ff");
+pub const DEFAULT_TEXT: Color = Color::hex("#ffffff");
+
+// Color scheme definitions. The built-in schemes borrow the constants above,
+// custom schemes defined at runtime own their name and colors.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColorScheme {
+    pub name: Cow<'static, str>,
+    pub color: Color,
+    pub shade: Color,
+    pub border: Color,
+    pub text: Color,
+}
+
+impl ColorScheme {
+    const fn built_in(
+        name: &'static str,
+        color: Color,
+        shade: Color,
+        border: Color,
+        text: Color,
+    ) -> Self {
+        Self {
+            name: Cow::Borrowed(name),
+            color,
+            shade,
+            border,
+            text,
+        }
+    }
+
+    pub fn custom(
+        name: impl Into<String>,
+        color: Color,
+        shade: Color,
+        border: Color,
+        text: Color,
+    ) -> Self {
+        Self {
+            name: Cow::Owned(name.into()),
+            color,
+            shade,
+            border,
+            text,
+        }
+    }
+
+    // A custom scheme with the shade, border and text color derived from its
+    // base color, in the style of the built-in schemes.
+    pub fn derive(name: impl Into<String>, color: Color) -> Self {
+        Self::custom(
+            name,
+            color,
+            derive_shade(color),
+            derive_border(color),
+            derive_text(color),
+        )
+    }
+
+    // whether this is one of the built-in schemes, unchanged
+    pub fn is_built_in(&self) -> bool {
+        ALL_COLOR_SCHEMES.contains(self)
+    }
+}
+
+pub const BLUE_SCHEME: ColorScheme =
+    ColorScheme::built_in("Blue", BLUE, BLUE_SHADE, DEFAULT_BORDER, BLACK_TEXT);
+
+pub const CYAN_SCHEME: ColorScheme =
+    ColorScheme::built_in("Cyan", CYAN, CYAN_SHADE, DEFAULT_BORDER, BLACK_TEXT);
+
+pub const DARK_BLUE_SCHEME: ColorScheme = ColorScheme::built_in(
+    "Dark Blue",
+    DARK_BLUE,
+    DARK_BLUE_SHADE,
+    DEFAULT_BORDER,
+    DEFAULT_TEXT,
+);
+
+pub const GOLD_SCHEME: ColorScheme =
+    ColorScheme::built_in("Gold", GOLD, GOLD_SHADE, DEFAULT_BORDER, BLACK_TEXT);
+
+pub const DARK_GREEN_SCHEME: ColorScheme = ColorScheme::built_in(
+    "Dark Green",
+    DARK_GREEN,
+    DARK_GREEN_SHADE,
+    DEFAULT_BORDER,
+    DEFAULT_TEXT,
+);
+
+pub const GREEN_SCHEME: ColorScheme =
+    ColorScheme::built_in("Green", GREEN, GREEN_SHADE, DEFAULT_BORDER, BLACK_TEXT);
+
+pub const LIGHT_ORANGE_SCHEME: ColorScheme = ColorScheme::built_in(
+    "Light Orange",
+    LIGHT_ORANGE,
+    LIGHT_ORANGE_SHADE,
+    DEFAULT_BORDER,
+    BLACK_TEXT,
+);
+
+pub const OLIVE_SCHEME: ColorScheme =
+    ColorScheme::built_in("Olive", OLIVE, OLIVE_SHADE, DEFAULT_BORDER, BLACK_TEXT);
+
+pub const ORANGE_SCHEME: ColorScheme =
+    ColorScheme::built_in("Orange", ORANGE, ORANGE_SHADE, DEFAULT_BORDER, BLACK_TEXT);
+
+pub const YELLOW_SCHEME: ColorScheme =
+    ColorScheme::built_in("Yellow", YELLOW, YELLOW_SHADE, DEFAULT_BORDER, BLACK_TEXT);
+
+pub const VIOLET_SCHEME: ColorScheme =
+    ColorScheme::built_in("Violet", VIOLET, VIOLET_SHADE, DEFAULT_BORDER, DEFAULT_TEXT);
+
+pub const PINK_SCHEME: ColorScheme =
+    ColorScheme::built_in("Pink", PINK, PINK_SHADE, DEFAULT_BORDER, BLACK_TEXT);
+
+pub const RED_SCHEME: ColorScheme =
+    ColorScheme::built_in("Red", RED, RED_SHADE, DEFAULT_BORDER, BLACK_TEXT);
+
+pub const WINERED_SCHEME: ColorScheme = ColorScheme::built_in(
+    "Wine Red",
+    WINERED,
+    WINERED_SHADE,
+    DEFAULT_BORDER,
+    DEFAULT_TEXT,
+);
+
+pub const WHITE_SCHEME: ColorScheme =
+    ColorScheme::built_in("White", WHITE, WHITE_SHADE, WHITE_BORDER, BLUE_TEXT);
+
+pub const TRANSPARENT_WHITE_SCHEME: ColorScheme = ColorScheme::built_in(
+    "Transparent White",
+    TRANSPARENT_WHITE,
+    TRANSPARENT_WHITE_SHADE,
+    TRANSPARENT_WHITE_BORDER,
+    BLACK_TEXT,
+);
+
+pub const TRANSPARENT_BLACK_SCHEME: ColorScheme = ColorScheme::built_in(
+    "Transparent Black",
+    TRANSPARENT_BLACK,
+    TRANSPARENT_BLACK_SHADE,
+    TRANSPARENT_BLACK_BORDER,
+    DEFAULT_TEXT,
+);
+
+pub const ALL_COLOR_SCHEMES: &[ColorScheme] = &[
+    BLUE_SCHEME,
+    CYAN_SCHEME,
+    DARK_BLUE_SCHEME,
+    GOLD_SCHEME,
+    DARK_GREEN_SCHEME,
+    GREEN_SCHEME,
+    LIGHT_ORANGE_SCHEME,
+    OLIVE_SCHEME,
+    ORANGE_SCHEME,
+    YELLOW_SCHEME,
+    VIOLET_SCHEME,
+    PINK_SCHEME,
+    RED_SCHEME,
+    WINERED_SCHEME,
+    WHITE_SCHEME,
+    TRANSPARENT_WHITE_SCHEME,
+    TRANSPARENT_BLACK_SCHEME,
+];
+
+// Looks up a built-in scheme by name, ignoring case, spaces, dashes and underscores,
+// so "Dark Blue", "dark-blue" and "DARK_BLUE" all resolve to the same scheme.
+pub fn find_color_scheme(name: &str) -> Option<ColorScheme> {
+    let normalize = |value: &str| {
+        value
+            .chars()
+            .filter(|c| !matches!(c, ' ' | '-' | '_'))
+            .map(|c| c.to_ascii_lowercase())
+            .collect::<String>()
+    };
+    let name = normalize(name);
+    ALL_COLOR_SCHEMES
+        .iter()
+        .find(|scheme| normalize(&scheme.name) == name)
+        .cloned()
+}
+
+// Built-in schemes are stored by name and resolved with `find_color_scheme`
+// when loaded. Custom schemes are stored with all their colors.
+#[derive(Serialize, Deserialize)]
+#[serde(
+    untagged,
+    expecting = "the name of a color scheme, or a scheme with name, color, shade, border and text"
+)]
+enum StoredScheme {
+    Name(String),
+    // colors are read as text, so an invalid one is reported by name
+    Custom {
+        name: String,
+        color: String,
+        shade: String,
+        border: String,
+        text: String,
+    },
+}
+
+impl Serialize for ColorScheme {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.is_built_in() {
+            return serializer.serialize_str(&self.name);
+        }
+        StoredScheme::Custom {
+            name: self.name.to_string(),
+            color: self.color.to_hex(),
+            shade: self.shade.to_hex(),
+            border: self.border.to_hex(),
+            text: self.text.to_hex(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ColorScheme {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match StoredScheme::deserialize(deserializer)? {
+            StoredScheme::Name(name) => find_color_scheme(&name)
+                .ok_or_else(|| D::Error::custom(format!("unknown color scheme '{name}'"))),
+            StoredScheme::Custom {
+                name,
+                color,
+                shade,
+                border,
+                text,
+            } => {
+                let parse = |field: &str, value: &str| {
+                    Color::parse(value).map_err(|error| {
+                        D::Error::custom(format!("{field} of color scheme '{name}': {error}"))
+                    })
+                };
+                Ok(Self::custom(
+                    name.clone(),
+                    parse("color", &color)?,
+                    parse("shade", &shade)?,
+                    parse("border", &border)?,
+                    parse("text", &text)?,
+                ))
+            }
+        }
+    }
+}
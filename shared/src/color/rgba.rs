@@ -0,0 +1,261 @@
+use crate::format::num;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+// An sRGB color with an alpha channel, written in hex notation: #rgb, #rgba,
+// #rrggbb or #rrggbbaa. The alpha channel comes last, like in CSS, so
+// #ffffff80 is half transparent white and #ffffffff is opaque.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseColorError(String);
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "'{}' is not a hex color like #rgb, #rgba, #rrggbb or #rrggbbaa",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseColorError {}
+
+const fn hex_digit(byte: u8) -> Option<u8> {
+    match byte {
+        b'0'..=b'9' => Some(byte - b'0'),
+        b'a'..=b'f' => Some(byte - b'a' + 10),
+        b'A'..=b'F' => Some(byte - b'A' + 10),
+        _ => None,
+    }
+}
+
+// const so the color constants can be written in hex
+const fn parse_hex(bytes: &[u8]) -> Option<Color> {
+    let [b'#', digits @ ..] = bytes else {
+        return None;
+    };
+    let (channels, width) = match digits.len() {
+        3 => (3, 1),
+        4 => (4, 1),
+        6 => (3, 2),
+        8 => (4, 2),
+        _ => return None,
+    };
+    let mut values = [0, 0, 0, u8::MAX];
+    let mut index = 0;
+    while index < channels {
+        let Some(high) = hex_digit(digits[index * width]) else {
+            return None;
+        };
+        // a single digit stands for itself twice, #f80 is #ff8800
+        let low = if width == 1 {
+            high
+        } else {
+            match hex_digit(digits[index * width + 1]) {
+                Some(low) => low,
+                None => return None,
+            }
+        };
+        values[index] = high * 16 + low;
+        index += 1;
+    }
+    Some(Color::rgba(values[0], values[1], values[2], values[3]))
+}
+
+impl Color {
+    pub const BLACK: Self = Self::rgb(0, 0, 0);
+    pub const WHITE: Self = Self::rgb(255, 255, 255);
+    pub const TRANSPARENT: Self = Self::rgba(0, 0, 0, 0);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self::rgba(r, g, b, u8::MAX)
+    }
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+
+    // For constants, an invalid color fails the build.
+    pub const fn hex(value: &str) -> Self {
+        match parse_hex(value.as_bytes()) {
+            Some(color) => color,
+            None => panic!("invalid hex color"),
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, ParseColorError> {
+        parse_hex(value.trim().as_bytes()).ok_or_else(|| ParseColorError(value.to_string()))
+    }
+
+    #[must_use]
+    pub const fn with_alpha(self, a: u8) -> Self {
+        Self { a, ..self }
+    }
+
+    pub const fn is_opaque(self) -> bool {
+        self.a == u8::MAX
+    }
+
+    // alpha from 0 to 1
+    pub fn opacity(self) -> f32 {
+        f32::from(self.a) / 255.0
+    }
+
+    // #rrggbb, without the alpha channel
+    pub fn to_rgb_hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+
+    // #rrggbb for opaque colors, #rrggbbaa otherwise
+    pub fn to_hex(self) -> String {
+        if self.is_opaque() {
+            self.to_rgb_hex()
+        } else {
+            format!("{}{:02x}", self.to_rgb_hex(), self.a)
+        }
+    }
+
+    // CSS value, with rgba() for transparent colors so older browsers agree
+    pub fn to_css(self) -> String {
+        if self.is_opaque() {
+            self.to_rgb_hex()
+        } else {
+            format!(
+                "rgba({}, {}, {}, {})",
+                self.r,
+                self.g,
+                self.b,
+                num(self.opacity())
+            )
+        }
+    }
+
+    // SVG paint declarations for a property like `fill` or `stop-color`. The
+    // alpha channel goes into the matching opacity property, since not every
+    // SVG renderer reads #rrggbbaa.
+    pub fn to_svg_paint(self, property: &str) -> String {
+        self.to_svg_paint_with_opacity(property, 1.0)
+    }
+
+    // like `to_svg_paint`, with the opacity scaled by `opacity`
+    pub fn to_svg_paint_with_opacity(self, property: &str, opacity: f32) -> String {
+        let opacity = self.opacity() * opacity;
+        let opacity_property = match property {
+            "stop-color" => "stop-opacity",
+            "stroke" => "stroke-opacity",
+            _ => "fill-opacity",
+        };
+        let paint = format!("{property}: {};", self.to_rgb_hex());
+        if opacity < 1.0 {
+            format!("{paint} {opacity_property}: {};", num(opacity))
+        } else {
+            paint
+        }
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::parse(value)
+    }
+}
+
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_hex())
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Self::parse(&value).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_hex_notation() {
+        assert_eq!(Color::parse("#f80"), Ok(Color::rgb(0xff, 0x88, 0x00)));
+        assert_eq!(
+            Color::parse("#f808"),
+            Ok(Color::rgba(0xff, 0x88, 0x00, 0x88))
+        );
+        assert_eq!(Color::parse("#408AC5"), Ok(Color::rgb(0x40, 0x8a, 0xc5)));
+        assert_eq!(
+            Color::parse(" #ffffff80 "),
+            Ok(Color::rgba(255, 255, 255, 0x80))
+        );
+        for invalid in [
+            "", "#", "408ac5", "#408ac", "#408ac5f", "#40 8ac", "#gggggg", "red",
+        ] {
+            assert!(Color::parse(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn writes_hex_and_css() {
+        let opaque = Color::hex("#408ac5");
+        assert_eq!(opaque.to_hex(), "#408ac5");
+        assert_eq!(opaque.to_css(), "#408ac5");
+        assert_eq!(opaque.to_string(), "#408ac5");
+
+        let translucent = Color::hex("#00000080");
+        assert_eq!(translucent.to_rgb_hex(), "#000000");
+        assert_eq!(translucent.to_hex(), "#00000080");
+        assert_eq!(translucent.to_css(), "rgba(0, 0, 0, 0.502)");
+        assert_eq!(Color::parse(&translucent.to_hex()), Ok(translucent));
+
+        let json = serde_json::to_string(&translucent).unwrap();
+        assert_eq!(json, "\"#00000080\"");
+        assert_eq!(serde_json::from_str::<Color>(&json).unwrap(), translucent);
+        assert!(serde_json::from_str::<Color>("\"#12345\"").is_err());
+    }
+
+    #[test]
+    fn alpha_goes_into_the_opacity_property() {
+        assert!(Color::WHITE.is_opaque());
+        assert!(!Color::TRANSPARENT.is_opaque());
+        assert!((Color::hex("#0000").opacity()).abs() < f32::EPSILON);
+        assert_eq!(Color::BLACK.with_alpha(0x40), Color::hex("#00000040"));
+
+        assert_eq!(Color::hex("#408ac5").to_svg_paint("fill"), "fill: #408ac5;");
+        assert_eq!(
+            Color::hex("#ffffff80").to_svg_paint("fill"),
+            "fill: #ffffff; fill-opacity: 0.502;"
+        );
+        assert_eq!(
+            Color::hex("#000000cc").to_svg_paint("stroke"),
+            "stroke: #000000; stroke-opacity: 0.8;"
+        );
+        assert_eq!(
+            Color::hex("#ffffff40").to_svg_paint("stop-color"),
+            "stop-color: #ffffff; stop-opacity: 0.251;"
+        );
+        assert_eq!(
+            Color::hex("#408ac5").to_svg_paint_with_opacity("fill", 0.5),
+            "fill: #408ac5; fill-opacity: 0.5;"
+        );
+    }
+}
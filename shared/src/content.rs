@@ -0,0 +1,267 @@
+use crate::layout::{
+    ContentArea, DROPDOWN_ARROW, FIELD_HEIGHT, FIELD_PADDING, FONT_FAMILY, FONT_SIZE, LINE_HEIGHT,
+    PlacedPart, layout_content, text_width,
+};
+use crate::svg::SvgElement;
+
+// Brick content is written in a small markup language:
+//
+//   Move {10} steps          {..} value bubble (formula field)
+//   Set [variable] to {0}    [..] dropdown pill
+//   Change <score> by {1}    <..> variable chip
+//
+// A backslash escapes the next character, also inside brackets, e.g. `\{`
+// for a literal brace or `{a\}b}` for a value containing one.
+// Unterminated brackets are kept as plain text.
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ContentPart {
+    Text(String),
+    Value(String),
+    Dropdown(String),
+    Variable(String),
+}
+
+impl ContentPart {
+    const fn delimiters(&self) -> Option<(char, char)> {
+        match self {
+            Self::Text(_) => None,
+            Self::Value(_) => Some(('{', '}')),
+            Self::Dropdown(_) => Some(('[', ']')),
+            Self::Variable(_) => Some(('<', '>')),
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            Self::Text(text) | Self::Value(text) | Self::Dropdown(text) | Self::Variable(text) => {
+                text
+            }
+        }
+    }
+}
+
+// Reads a label up to the unescaped `close`, returning it unescaped together
+// with the number of bytes consumed, closing delimiter included.
+fn scan_label(markup: &str, close: char) -> Option<(String, usize)> {
+    let mut label = String::new();
+    let mut chars = markup.char_indices();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' => label.push(chars.next().map_or('\\', |(_, c)| c)),
+            c if c == close => return Some((label, index + c.len_utf8())),
+            c => label.push(c),
+        }
+    }
+    None
+}
+
+pub fn parse_content(markup: &str) -> Vec<ContentPart> {
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    // closing delimiters missing from the rest of the markup, so runs of
+    // unterminated brackets are not scanned over and over
+    let mut unclosed = Vec::new();
+    let mut chars = markup.chars();
+
+    while let Some(c) = chars.next() {
+        let close = match c {
+            '\\' => {
+                text.push(chars.next().unwrap_or('\\'));
+                continue;
+            }
+            '{' => '}',
+            '[' => ']',
+            '<' => '>',
+            _ => {
+                text.push(c);
+                continue;
+            }
+        };
+
+        let rest = chars.as_str();
+        let scanned = (!unclosed.contains(&close))
+            .then(|| scan_label(rest, close))
+            .flatten();
+        let Some((label, length)) = scanned else {
+            if !unclosed.contains(&close) {
+                unclosed.push(close);
+            }
+            text.push(c);
+            continue;
+        };
+        chars = rest[length..].chars();
+
+        if !text.is_empty() {
+            parts.push(ContentPart::Text(std::mem::take(&mut text)));
+        }
+        parts.push(match c {
+            '{' => ContentPart::Value(label),
+            '[' => ContentPart::Dropdown(label),
+            _ => ContentPart::Variable(label),
+        });
+    }
+    if !text.is_empty() {
+        parts.push(ContentPart::Text(text));
+    }
+    parts
+}
+
+pub fn to_markup(parts: &[ContentPart]) -> String {
+    let escape = |text: &str| {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            if matches!(c, '\\' | '{' | '}' | '[' | ']' | '<' | '>') {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        escaped
+    };
+
+    parts
+        .iter()
+        .map(|part| match part.delimiters() {
+            None => escape(part.label()),
+            Some((open, close)) => format!("{open}{}{close}", escape(part.label())),
+        })
+        .collect()
+}
+
+// `textLength` pins every run to its measured width, so fonts with other
+// metrics than the table in `layout` cannot push text into the next field.
+fn text(x: f32, y: f32, class: &str, label: &str) -> SvgElement {
+    SvgElement::new("text")
+        .attr("x", x.to_string())
+        .attr("y", y.to_string())
+        .attr("class", class)
+        .attr("dominant-baseline", "middle")
+        .attr("font-family", FONT_FAMILY)
+        .attr("font-size", FONT_SIZE.to_string())
+        .attr("textLength", text_width(label).to_string())
+        .attr("lengthAdjust", "spacingAndGlyphs")
+        .text(label)
+}
+
+fn field(x: f32, y: f32, width: f32, class: &str, radius: f32) -> SvgElement {
+    SvgElement::new("rect")
+        .attr("x", x.to_string())
+        .attr("y", (y - FIELD_HEIGHT / 2.0).to_string())
+        .attr("width", width.to_string())
+        .attr("height", FIELD_HEIGHT.to_string())
+        .attr("rx", radius.to_string())
+        .attr("class", class)
+}
+
+// Wraps the parts to the content area and draws them left aligned, with the
+// lines centered vertically.
+pub fn content_to_svg(parts: &[ContentPart], area: &ContentArea) -> SvgElement {
+    let layout = layout_content(parts, area.width);
+    let top = area.y + (area.height - layout.height()) / 2.0;
+
+    let mut group = SvgElement::new("g").attr("class", "content");
+    for (index, line) in layout.lines.iter().enumerate() {
+        let y = (index as f32).mul_add(LINE_HEIGHT, top + LINE_HEIGHT / 2.0);
+        for PlacedPart { part, x, width } in &line.parts {
+            let x = area.x + x;
+            group = match part {
+                ContentPart::Text(label) => group.child(text(x, y, "text", label)),
+                ContentPart::Value(label) => group
+                    .child(field(x, y, *width, "value-field", FIELD_HEIGHT / 2.0))
+                    .child(text(x + FIELD_PADDING, y, "value-text", label)),
+                ContentPart::Dropdown(label) => group
+                    .child(field(x, y, *width, "dropdown-field", FIELD_HEIGHT / 2.0))
+                    .child(text(
+                        x + FIELD_PADDING,
+                        y,
+                        "text",
+                        &format!("{label} {DROPDOWN_ARROW}"),
+                    )),
+                ContentPart::Variable(label) => group
+                    .child(field(x, y, *width, "variable-field", 2.0))
+                    .child(text(x + FIELD_PADDING, y, "text", label)),
+            };
+        }
+    }
+    group
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(text: &str) -> ContentPart {
+        ContentPart::Text(text.into())
+    }
+
+    #[test]
+    fn markup_is_parsed_into_parts() {
+        assert_eq!(
+            parse_content("Set [size] of <pen> to {10 + 2}"),
+            [
+                text("Set "),
+                ContentPart::Dropdown("size".into()),
+                text(" of "),
+                ContentPart::Variable("pen".into()),
+                text(" to "),
+                ContentPart::Value("10 + 2".into()),
+            ]
+        );
+        assert_eq!(parse_content(""), []);
+        assert_eq!(parse_content("{}"), [ContentPart::Value(String::new())]);
+        // a different opening bracket inside a label is part of it
+        assert_eq!(parse_content("{a [b}"), [ContentPart::Value("a [b".into())]);
+    }
+
+    #[test]
+    fn backslashes_escape_delimiters() {
+        assert_eq!(parse_content(r"\{10\} \\ end\"), [text(r"{10} \ end\")]);
+        assert_eq!(
+            parse_content(r"{a\}b} [x\]] <\\>"),
+            [
+                ContentPart::Value("a}b".into()),
+                text(" "),
+                ContentPart::Dropdown("x]".into()),
+                text(" "),
+                ContentPart::Variable(r"\".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn unterminated_brackets_are_text() {
+        assert_eq!(parse_content("a {b"), [text("a {b")]);
+        assert_eq!(
+            parse_content("{a [b] <c"),
+            [text("{a "), ContentPart::Dropdown("b".into()), text(" <c")]
+        );
+        assert_eq!(parse_content(r"{a\}"), [text("{a}")]);
+
+        // scanning stays linear in the length of the markup
+        let markup = "{[<".repeat(100_000);
+        assert_eq!(parse_content(&markup), [text(&markup)]);
+    }
+
+    #[test]
+    fn markup_round_trips() {
+        let parts = [
+            text("literal {braces} and [brackets] <here> \\ "),
+            ContentPart::Value("a}b".into()),
+            ContentPart::Dropdown(r"x]y\".into()),
+            ContentPart::Variable("<v>".into()),
+            text(" {"),
+        ];
+        let markup = to_markup(&parts);
+        assert_eq!(
+            markup,
+            concat!(
+                r"literal \{braces\} and \[brackets\] \<here\> \\ ",
+                r"{a\}b}[x\]y\\]<\<v\>> \{"
+            )
+        );
+        assert_eq!(parse_content(&markup), parts);
+
+        let markup = "Move {10} steps, turn [left] by <angle>";
+        assert_eq!(to_markup(&parse_content(markup)), markup);
+    }
+}
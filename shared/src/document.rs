@@ -0,0 +1,246 @@
+use crate::brick::base::BrickSVG;
+use crate::brick::create_brick;
+use crate::color::{BLUE_SCHEME, ColorScheme};
+use crate::layout::fit_brick_type;
+use crate::types::BrickType;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+// Version of the document format written by this build. Documents with a
+// newer version are rejected instead of being silently misread.
+//
+// 1: type, scheme, content and parameters
+// 2: optional brick width and height
+// 3: custom color schemes, stored with their colors instead of a name
+pub const DOCUMENT_VERSION: u32 = 3;
+
+// Serializable description of a single brick, shared by the frontend, the
+// backend and the command line tools.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BrickSpec {
+    #[serde(rename = "type")]
+    pub brick_type: BrickType,
+    #[serde(rename = "scheme", default = "default_color_scheme")]
+    pub color_scheme: ColorScheme,
+    #[serde(default)]
+    pub content: String,
+    // named values for the parameter slots of the brick
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub parameters: BTreeMap<String, String>,
+    // size in SVG units, the default size of the type when missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<f32>,
+}
+
+const fn default_color_scheme() -> ColorScheme {
+    BLUE_SCHEME
+}
+
+impl BrickSpec {
+    pub fn new(brick_type: BrickType, color_scheme: ColorScheme, content: &str) -> Self {
+        Self {
+            brick_type,
+            color_scheme,
+            content: content.to_string(),
+            parameters: BTreeMap::new(),
+            width: None,
+            height: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_size(mut self, width: Option<f32>, height: Option<f32>) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    // Creates the brick, growing it to a taller variant when the content
+    // needs more lines than the requested type holds. An explicit height
+    // turns the automatic growth off.
+    pub fn to_brick(&self) -> Box<dyn BrickSVG> {
+        let brick_type = if self.height.is_some() {
+            self.brick_type
+        } else {
+            fit_brick_type(self.brick_type, &self.content, self.width)
+        };
+        let mut brick = create_brick(brick_type);
+        if self.width.is_some() || self.height.is_some() {
+            let (width, height) = brick.size();
+            brick.set_size(self.width.unwrap_or(width), self.height.unwrap_or(height));
+        }
+        brick.set_content(&self.content);
+        brick
+    }
+
+    pub fn to_svg_string(&self) -> String {
+        self.to_brick().to_svg_string(&self.color_scheme)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BrickDocument {
+    pub version: u32,
+    #[serde(default)]
+    pub bricks: Vec<BrickSpec>,
+}
+
+#[derive(Debug)]
+pub enum DocumentError {
+    Parse(String),
+    Serialize(String),
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for DocumentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(message) => write!(f, "could not read brick document: {message}"),
+            Self::Serialize(message) => write!(f, "could not write brick document: {message}"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "brick document version {version} is not supported (expected 1 to {DOCUMENT_VERSION})"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DocumentError {}
+
+impl BrickDocument {
+    pub const fn new(bricks: Vec<BrickSpec>) -> Self {
+        Self {
+            version: DOCUMENT_VERSION,
+            bricks,
+        }
+    }
+
+    fn checked(self) -> Result<Self, DocumentError> {
+        if self.version == 0 || self.version > DOCUMENT_VERSION {
+            return Err(DocumentError::UnsupportedVersion(self.version));
+        }
+        Ok(self)
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, DocumentError> {
+        serde_json::from_str::<Self>(text)
+            .map_err(|error| DocumentError::Parse(error.to_string()))?
+            .checked()
+    }
+
+    pub fn to_json(&self) -> Result<String, DocumentError> {
+        serde_json::to_string_pretty(self)
+            .map_err(|error| DocumentError::Serialize(error.to_string()))
+    }
+
+    #[cfg(feature = "toml")]
+    pub fn from_toml(text: &str) -> Result<Self, DocumentError> {
+        toml::from_str::<Self>(text)
+            .map_err(|error| DocumentError::Parse(error.to_string()))?
+            .checked()
+    }
+
+    #[cfg(feature = "toml")]
+    pub fn to_toml(&self) -> Result<String, DocumentError> {
+        toml::to_string_pretty(self).map_err(|error| DocumentError::Serialize(error.to_string()))
+    }
+
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(text: &str) -> Result<Self, DocumentError> {
+        serde_yaml::from_str::<Self>(text)
+            .map_err(|error| DocumentError::Parse(error.to_string()))?
+            .checked()
+    }
+
+    #[cfg(feature = "yaml")]
+    pub fn to_yaml(&self) -> Result<String, DocumentError> {
+        serde_yaml::to_string(self).map_err(|error| DocumentError::Serialize(error.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::{Color, DARK_BLUE_SCHEME};
+
+    fn document() -> BrickDocument {
+        let mut repeat = BrickSpec::new(BrickType::Repeat, DARK_BLUE_SCHEME, "Repeat {10} times")
+            .with_size(Some(320.0), None);
+        repeat
+            .parameters
+            .insert("times".to_string(), "10".to_string());
+        let custom = ColorScheme::derive("Mint", Color::hex("#2a9d8f"));
+        let note =
+            BrickSpec::new(BrickType::H2Base, custom, "Two\nlines").with_size(None, Some(90.5));
+        BrickDocument::new(vec![repeat, note])
+    }
+
+    #[test]
+    fn json_round_trips() {
+        let document = document();
+        let json = document.to_json().unwrap();
+        assert_eq!(BrickDocument::from_json(&json).unwrap(), document);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml_round_trips() {
+        let document = document();
+        let toml = document.to_toml().unwrap();
+        assert_eq!(BrickDocument::from_toml(&toml).unwrap(), document);
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn yaml_round_trips() {
+        let document = document();
+        let yaml = document.to_yaml().unwrap();
+        assert_eq!(BrickDocument::from_yaml(&yaml).unwrap(), document);
+    }
+
+    #[test]
+    fn unknown_versions_are_rejected() {
+        for version in [0, DOCUMENT_VERSION + 1] {
+            let json = format!(r#"{{"version": {version}, "bricks": []}}"#);
+            assert!(matches!(
+                BrickDocument::from_json(&json),
+                Err(DocumentError::UnsupportedVersion(found)) if found == version
+            ));
+        }
+        assert!(matches!(
+            BrickDocument::from_json(r#"{"bricks": []}"#),
+            Err(DocumentError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn older_versions_still_load() {
+        // 1: no sizes, schemes by name
+        let version_1 = BrickDocument::from_json(
+            r#"{"version": 1, "bricks": [
+                {"type": "H1Base", "scheme": "Blue", "content": "Hello", "parameters": {"name": "x"}},
+                {"type": "H0Collapsed"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(version_1.version, 1);
+        assert_eq!(version_1.bricks[0].color_scheme, BLUE_SCHEME);
+        assert_eq!(version_1.bricks[0].parameters["name"], "x");
+        assert_eq!(version_1.bricks[1].content, "");
+        assert_eq!(version_1.bricks[1].width, None);
+
+        // 2: sizes
+        let version_2 = BrickDocument::from_json(
+            r#"{"version": 2, "bricks": [
+                {"type": "H1Base", "scheme": "dark-blue", "width": 300, "height": 60}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(version_2.bricks[0].color_scheme, DARK_BLUE_SCHEME);
+        assert_eq!(version_2.bricks[0].width, Some(300.0));
+        assert_eq!(version_2.bricks[0].height, Some(60.0));
+    }
+}
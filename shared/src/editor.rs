@@ -0,0 +1,363 @@
+use crate::category::BrickCategory;
+use crate::color::{BLUE_SCHEME, ColorScheme};
+use crate::document::BrickSpec;
+use crate::script::{BrickPosition, Script, ScriptBrick};
+use crate::types::BrickType;
+use serde::{Deserialize, Serialize};
+
+// Editor state and the commands that change it. `reduce` is a pure function
+// of the state and a command, and `History` keeps the states before and after
+// each command for undo and redo. View settings like the collapsed sidebar or
+// the PNG width are not part of the history.
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EditorState {
+    // the brick edited in the panel
+    pub brick: BrickSpec,
+    #[serde(default)]
+    pub script: Script,
+    // brick of the script the panel edits, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selected: Option<BrickPosition>,
+}
+
+impl Default for EditorState {
+    fn default() -> Self {
+        Self {
+            brick: BrickSpec::new(BrickType::H0Collapsed, BLUE_SCHEME, ""),
+            script: Script::default(),
+            selected: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    SetType(BrickType),
+    SetColorScheme(ColorScheme),
+    // the colors of the category, and its first shape if the current one
+    // does not belong to it
+    SetCategory(BrickCategory),
+    SetContent(String),
+    // replaces the panel brick, e.g. with one picked from the catalog
+    SetBrick(BrickSpec),
+    SetWidth(Option<f32>),
+    SetHeight(Option<f32>),
+    // inserts before the brick at the position and selects the new brick
+    InsertBrick(BrickPosition, ScriptBrick),
+    MoveBrick {
+        from: BrickPosition,
+        to: BrickPosition,
+    },
+    RemoveBrick(BrickPosition),
+    // loads a brick of the script into the panel, or detaches the panel
+    Select(Option<BrickPosition>),
+    // back to the default brick and an empty script, undoable like any edit
+    Reset,
+    // replaces the whole state, e.g. with one opened from a share link
+    Load(EditorState),
+}
+
+impl Command {
+    // Commands that only move the focus are not undo steps of their own.
+    pub const fn is_edit(&self) -> bool {
+        !matches!(self, Self::Select(_))
+    }
+
+    // Consecutive edits of the same field, like typing, are undone together.
+    pub fn merges_with(&self, previous: &Self) -> bool {
+        matches!(
+            (previous, self),
+            (Self::SetContent(_), Self::SetContent(_))
+                | (Self::SetWidth(_), Self::SetWidth(_))
+                | (Self::SetHeight(_), Self::SetHeight(_))
+        )
+    }
+}
+
+fn select(state: &mut EditorState, position: Option<BrickPosition>) {
+    if let Some(brick) = position
+        .as_ref()
+        .and_then(|position| state.script.get(position))
+    {
+        state.brick = brick.brick.clone();
+    }
+    state.selected = position;
+}
+
+// writes the panel back into the selected brick, keeping its slots
+fn store_selected(state: &mut EditorState) {
+    let brick = state.brick.clone();
+    if let Some(selected) = state
+        .selected
+        .as_ref()
+        .and_then(|position| state.script.get_mut(position))
+    {
+        selected.brick = brick;
+    }
+}
+
+pub fn reduce(state: &EditorState, command: &Command) -> EditorState {
+    let mut state = state.clone();
+    match command {
+        Command::SetType(brick_type) => {
+            // the height belongs to the old shape, the width carries over
+            state.brick.brick_type = *brick_type;
+            state.brick.height = None;
+            store_selected(&mut state);
+        }
+        Command::SetColorScheme(color_scheme) => {
+            state.brick.color_scheme.clone_from(color_scheme);
+            store_selected(&mut state);
+        }
+        Command::SetCategory(category) => {
+            state.brick.color_scheme = category.color_scheme();
+            if !category.allows(state.brick.brick_type) {
+                state.brick.brick_type = category.brick_types()[0];
+                state.brick.height = None;
+            }
+            store_selected(&mut state);
+        }
+        Command::SetContent(content) => {
+            state.brick.content.clone_from(content);
+            store_selected(&mut state);
+        }
+        Command::SetBrick(brick) => {
+            state.brick.clone_from(brick);
+            store_selected(&mut state);
+        }
+        Command::SetWidth(width) => {
+            state.brick.width = *width;
+            store_selected(&mut state);
+        }
+        Command::SetHeight(height) => {
+            state.brick.height = *height;
+            store_selected(&mut state);
+        }
+        Command::InsertBrick(position, brick) => {
+            if state.script.insert(position, brick.clone()) {
+                select(&mut state, Some(position.clone()));
+            }
+        }
+        Command::MoveBrick { from, to } => {
+            if let Some(moved) = state.script.move_brick(from, to) {
+                select(&mut state, Some(moved));
+            }
+        }
+        Command::RemoveBrick(position) => {
+            if state.script.remove(position).is_some() {
+                state.selected = None;
+            }
+        }
+        Command::Select(position) => select(&mut state, position.clone()),
+        Command::Reset => state = EditorState::default(),
+        Command::Load(loaded) => state.clone_from(loaded),
+    }
+    state
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct History {
+    past: Vec<EditorState>,
+    present: EditorState,
+    future: Vec<EditorState>,
+    // last recorded command, to merge runs of the same edit
+    last: Option<Command>,
+}
+
+impl History {
+    // oldest states are dropped beyond this many undo steps
+    pub const LIMIT: usize = 200;
+
+    pub const fn new(state: EditorState) -> Self {
+        Self {
+            past: Vec::new(),
+            present: state,
+            future: Vec::new(),
+            last: None,
+        }
+    }
+
+    pub const fn state(&self) -> &EditorState {
+        &self.present
+    }
+
+    // Applies the command and returns whether the state changed. Commands
+    // without an effect leave the history untouched.
+    pub fn apply(&mut self, command: Command) -> bool {
+        let next = reduce(&self.present, &command);
+        if next == self.present {
+            return false;
+        }
+        if !command.is_edit() {
+            self.present = next;
+            self.last = None;
+            return true;
+        }
+
+        let merge = self
+            .last
+            .as_ref()
+            .is_some_and(|last| command.merges_with(last));
+        if merge {
+            self.present = next;
+        } else {
+            self.past.push(std::mem::replace(&mut self.present, next));
+            if self.past.len() > Self::LIMIT {
+                self.past.remove(0);
+            }
+        }
+        self.future.clear();
+        self.last = Some(command);
+        true
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.past.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.future.is_empty()
+    }
+
+    pub fn undo(&mut self) -> bool {
+        let Some(previous) = self.past.pop() else {
+            return false;
+        };
+        self.future
+            .push(std::mem::replace(&mut self.present, previous));
+        self.last = None;
+        true
+    }
+
+    pub fn redo(&mut self) -> bool {
+        let Some(next) = self.future.pop() else {
+            return false;
+        };
+        self.past.push(std::mem::replace(&mut self.present, next));
+        self.last = None;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn content(history: &History) -> &str {
+        &history.state().brick.content
+    }
+
+    #[test]
+    fn undo_and_redo_walk_the_history() {
+        let mut history = History::default();
+        assert!(!history.undo());
+        assert!(history.apply(Command::SetType(BrickType::H1Base)));
+        assert!(history.apply(Command::SetContent("Hello".to_string())));
+
+        assert!(history.undo());
+        assert_eq!(content(&history), "");
+        assert_eq!(history.state().brick.brick_type, BrickType::H1Base);
+        assert!(history.undo());
+        assert_eq!(history.state(), &EditorState::default());
+        assert!(!history.can_undo());
+
+        assert!(history.redo());
+        assert!(history.redo());
+        assert_eq!(content(&history), "Hello");
+        assert!(!history.redo());
+
+        // a new edit drops the undone states
+        history.undo();
+        assert!(history.apply(Command::SetWidth(Some(300.0))));
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn consecutive_edits_of_a_field_merge() {
+        let mut history = History::default();
+        for text in ["H", "He", "Hello"] {
+            history.apply(Command::SetContent(text.to_string()));
+        }
+        history.apply(Command::SetWidth(Some(300.0)));
+        history.apply(Command::SetWidth(Some(320.0)));
+        history.apply(Command::SetHeight(Some(80.0)));
+        history.apply(Command::SetHeight(Some(90.0)));
+        assert_eq!(history.past.len(), 3);
+
+        assert!(history.undo());
+        assert_eq!(history.state().brick.height, None);
+        assert!(history.undo());
+        assert_eq!(history.state().brick.width, None);
+        assert_eq!(content(&history), "Hello");
+        assert!(history.undo());
+        assert_eq!(content(&history), "");
+
+        // an undo ends the run, the next edit is a step of its own
+        history.redo();
+        history.undo();
+        history.apply(Command::SetContent("Hi".to_string()));
+        history.apply(Command::SetContent("Hi!".to_string()));
+        history.undo();
+        assert_eq!(content(&history), "");
+    }
+
+    #[test]
+    fn history_is_trimmed_at_the_limit() {
+        let mut history = History::default();
+        let edits = History::LIMIT + 10;
+        for step in 0..edits {
+            // alternating fields, so no edits merge
+            let command = if step % 2 == 0 {
+                Command::SetContent(step.to_string())
+            } else {
+                Command::SetWidth(Some(100.0 + step as f32))
+            };
+            assert!(history.apply(command));
+        }
+        assert_eq!(history.past.len(), History::LIMIT);
+
+        let mut undone = 0;
+        while history.undo() {
+            undone += 1;
+        }
+        assert_eq!(undone, History::LIMIT);
+        // the oldest states are gone, the ten first edits stay applied
+        assert_eq!(content(&history), "8");
+        assert_eq!(history.state().brick.width, Some(109.0));
+    }
+
+    #[test]
+    fn selecting_is_no_undo_step() {
+        let brick = ScriptBrick::new(BrickSpec::new(BrickType::H1Base, BLUE_SCHEME, "Move"));
+        let mut history = History::default();
+        history.apply(Command::InsertBrick(BrickPosition::top(0), brick));
+        assert_eq!(history.state().selected, Some(BrickPosition::top(0)));
+        let steps = history.past.len();
+
+        assert!(history.apply(Command::Select(None)));
+        assert_eq!(history.state().selected, None);
+        assert!(history.apply(Command::Select(Some(BrickPosition::top(0)))));
+        assert_eq!(content(&history), "Move");
+        assert_eq!(history.past.len(), steps);
+    }
+
+    #[test]
+    fn commands_without_effect_are_not_applied() {
+        let mut history = History::default();
+        let brick_type = history.state().brick.brick_type;
+        assert!(!history.apply(Command::SetType(brick_type)));
+        assert!(!history.apply(Command::SetContent(String::new())));
+        assert!(!history.apply(Command::Select(None)));
+        assert!(!history.apply(Command::RemoveBrick(BrickPosition::top(0))));
+        assert!(!history.apply(Command::Reset));
+        // moves and inserts into stacks that do not exist
+        let brick = ScriptBrick::new(BrickSpec::new(BrickType::H1Base, BLUE_SCHEME, ""));
+        assert!(!history.apply(Command::InsertBrick(BrickPosition::top(0).slot(0), brick)));
+        assert!(!history.apply(Command::MoveBrick {
+            from: BrickPosition::top(0),
+            to: BrickPosition::top(1),
+        }));
+        assert!(!history.can_undo());
+    }
+}
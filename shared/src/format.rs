@@ -0,0 +1,27 @@
+// Number formatting shared by the SVG drawing and the color notation.
+
+// rounds to three decimals so path data and colors stay readable
+pub fn num(value: f32) -> String {
+    let rounded = format!("{value:.3}");
+    let trimmed = rounded.trim_end_matches('0').trim_end_matches('.');
+    if trimmed == "-0" {
+        "0".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_are_short() {
+        assert_eq!(num(10.0), "10");
+        assert_eq!(num(0.5), "0.5");
+        assert_eq!(num(348.181_2), "348.181");
+        assert_eq!(num(2.999_9), "3");
+        assert_eq!(num(-1.25), "-1.25");
+        assert_eq!(num(-0.000_1), "0");
+    }
+}
@@ -0,0 +1,352 @@
+use crate::brick::create_brick;
+use crate::content::{ContentPart, parse_content};
+use crate::types::BrickType;
+
+// Text layout for brick content. Widths come from the metrics table below
+// instead of a font engine, so the server and the browser wrap identically.
+
+pub const FONT_SIZE: f32 = 12.0;
+// Arial, Helvetica and Liberation Sans share the metrics below.
+pub const FONT_FAMILY: &str = "Arial, Helvetica, 'Liberation Sans', sans-serif";
+pub const LINE_HEIGHT: f32 = 22.0;
+pub const FIELD_PADDING: f32 = 5.0;
+pub const FIELD_HEIGHT: f32 = FONT_SIZE + 6.0;
+pub const DROPDOWN_ARROW: &str = "\u{25be}";
+
+// space left of the content for the tab blocks, and on the other sides
+pub const CONTENT_LEFT: f32 = 45.0;
+pub const CONTENT_MARGIN: f32 = 8.0;
+
+// Advance widths of Arial/Helvetica in 1/1000 em for the printable ASCII
+// characters, starting with the space (32) and ending with the tilde (126).
+const ASCII_ADVANCES: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, // ' ' to '\''
+    333, 333, 389, 584, 278, 333, 278, 278, // '(' to '/'
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, // '0' to '9'
+    278, 278, 584, 584, 584, 556, 1015, // ':' to '@'
+    667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, // 'A' to 'M'
+    722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, // 'N' to 'Z'
+    278, 278, 278, 469, 556, 333, // '[' to '`'
+    556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, // 'a' to 'm'
+    556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, // 'n' to 'z'
+    334, 260, 334, 584, // '{' to '~'
+];
+const DEFAULT_ADVANCE: u16 = 556;
+const WIDE_ADVANCE: u16 = 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ContentArea {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl ContentArea {
+    // number of lines that fit, at least one
+    pub fn line_capacity(&self) -> usize {
+        ((self.height / LINE_HEIGHT).floor() as usize).max(1)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlacedPart {
+    pub part: ContentPart,
+    // offset from the start of the line
+    pub x: f32,
+    pub width: f32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Line {
+    pub parts: Vec<PlacedPart>,
+    pub width: f32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ContentLayout {
+    pub lines: Vec<Line>,
+}
+
+impl ContentLayout {
+    pub fn height(&self) -> f32 {
+        self.lines.len() as f32 * LINE_HEIGHT
+    }
+}
+
+fn char_advance(c: char) -> u16 {
+    match c {
+        ' '..='~' => ASCII_ADVANCES[c as usize - ' ' as usize],
+        // CJK, Hangul and full width forms
+        '\u{1100}'..='\u{115f}'
+        | '\u{2e80}'..='\u{a4cf}'
+        | '\u{ac00}'..='\u{d7a3}'
+        | '\u{f900}'..='\u{faff}'
+        | '\u{ff00}'..='\u{ff60}' => WIDE_ADVANCE,
+        _ => DEFAULT_ADVANCE,
+    }
+}
+
+pub fn text_width(text: &str) -> f32 {
+    let advance: u32 = text.chars().map(|c| u32::from(char_advance(c))).sum();
+    advance as f32 * FONT_SIZE / 1000.0
+}
+
+pub fn part_width(part: &ContentPart) -> f32 {
+    match part {
+        ContentPart::Text(text) => text_width(text),
+        ContentPart::Dropdown(label) => {
+            text_width(&format!("{label} {DROPDOWN_ARROW}")) + 2.0 * FIELD_PADDING
+        }
+        ContentPart::Value(label) | ContentPart::Variable(label) => {
+            text_width(label) + 2.0 * FIELD_PADDING
+        }
+    }
+}
+
+// Breaks the parts into lines of at most `max_width`. Text wraps between
+// words, fields are never split. A single token wider than the line gets a
+// line of its own.
+pub fn layout_content(parts: &[ContentPart], max_width: f32) -> ContentLayout {
+    let gap = text_width(" ");
+    let tokens = parts.iter().flat_map(|part| match part {
+        ContentPart::Text(text) => text
+            .split_whitespace()
+            .map(|word| ContentPart::Text(word.to_string()))
+            .collect::<Vec<_>>(),
+        field => vec![field.clone()],
+    });
+
+    let mut layout = ContentLayout::default();
+    let mut line = Line::default();
+    for token in tokens {
+        let width = part_width(&token);
+        if !line.parts.is_empty() && line.width + gap + width > max_width {
+            layout.lines.push(std::mem::take(&mut line));
+        }
+        let x = if line.parts.is_empty() {
+            0.0
+        } else {
+            line.width + gap
+        };
+
+        // consecutive words are drawn as a single text run
+        match (line.parts.last_mut(), token) {
+            (
+                Some(PlacedPart {
+                    part: ContentPart::Text(previous),
+                    width: previous_width,
+                    ..
+                }),
+                ContentPart::Text(word),
+            ) => {
+                previous.push(' ');
+                previous.push_str(&word);
+                *previous_width += gap + width;
+            }
+            (_, token) => line.parts.push(PlacedPart {
+                part: token,
+                x,
+                width,
+            }),
+        }
+        line.width = x + width;
+    }
+    if !line.parts.is_empty() {
+        layout.lines.push(line);
+    }
+    layout
+}
+
+const BASE_VARIANTS: &[BrickType] = &[BrickType::H1Base, BrickType::H2Base, BrickType::H3Base];
+const CONTROL_VARIANTS: &[BrickType] = &[BrickType::H1Control, BrickType::H2Control];
+
+// Returns the smallest height variant of the same brick family, starting at
+// `brick_type`, whose content area fits the wrapped content. Bricks are never
+// made smaller than requested. `width` overrides the default brick width.
+pub fn fit_brick_type(brick_type: BrickType, content: &str, width: Option<f32>) -> BrickType {
+    let variants = [BASE_VARIANTS, CONTROL_VARIANTS]
+        .into_iter()
+        .find(|variants| variants.contains(&brick_type))
+        .unwrap_or(&[]);
+    let Some(start) = variants.iter().position(|variant| *variant == brick_type) else {
+        return brick_type;
+    };
+
+    let parts = parse_content(content);
+    variants[start..]
+        .iter()
+        .copied()
+        .find(|variant| {
+            let mut brick = create_brick(*variant);
+            if let Some(width) = width {
+                brick.set_size(width, brick.size().1);
+            }
+            let area = brick.content_area();
+            layout_content(&parts, area.width).lines.len() <= area.line_capacity()
+        })
+        .unwrap_or_else(|| variants[variants.len() - 1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-3,
+            "{actual} is not {expected}"
+        );
+    }
+
+    fn line_texts(layout: &ContentLayout) -> Vec<Vec<ContentPart>> {
+        layout
+            .lines
+            .iter()
+            .map(|line| {
+                line.parts
+                    .iter()
+                    .map(|placed| placed.part.clone())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn text_widths_come_from_the_metrics() {
+        // H e l l o
+        assert_close(
+            text_width("Hello"),
+            (722 + 556 + 222 + 222 + 556) as f32 * 0.012,
+        );
+        assert_close(text_width(""), 0.0);
+        assert_close(text_width("中文"), 2.0 * FONT_SIZE);
+        assert_close(text_width("é"), text_width("a"));
+        assert_close(
+            part_width(&ContentPart::Value("10".into())),
+            text_width("10") + 2.0 * FIELD_PADDING,
+        );
+        assert_close(
+            part_width(&ContentPart::Dropdown("meow".into())),
+            text_width("meow \u{25be}") + 2.0 * FIELD_PADDING,
+        );
+    }
+
+    #[test]
+    fn text_wraps_at_the_line_limit() {
+        let parts = parse_content("one two three");
+        let two_words = text_width("one two");
+        let layout = layout_content(&parts, two_words + 0.01);
+        assert_eq!(
+            line_texts(&layout),
+            [
+                vec![ContentPart::Text("one two".into())],
+                vec![ContentPart::Text("three".into())],
+            ]
+        );
+        assert_close(layout.lines[0].width, two_words);
+        assert_close(layout.height(), 2.0 * LINE_HEIGHT);
+
+        let layout = layout_content(&parts, two_words - 0.01);
+        assert_eq!(layout.lines.len(), 3);
+        assert!(layout_content(&[], 100.0).lines.is_empty());
+    }
+
+    #[test]
+    fn long_words_get_a_line_of_their_own() {
+        let word = "Supercalifragilisticexpialidocious";
+        let layout = layout_content(&parse_content(&format!("a {word} b")), 50.0);
+        assert_eq!(
+            line_texts(&layout),
+            [
+                vec![ContentPart::Text("a".into())],
+                vec![ContentPart::Text(word.into())],
+                vec![ContentPart::Text("b".into())],
+            ]
+        );
+        assert!(layout.lines[1].width > 50.0);
+        assert_close(layout.lines[1].parts[0].x, 0.0);
+    }
+
+    #[test]
+    fn layout_is_pinned() {
+        let layout = layout_content(
+            &parse_content("Set <score> to {10} and [look 1] now"),
+            150.0,
+        );
+        let expected = [
+            vec![
+                (ContentPart::Text("Set".into()), 0.0, 18.012),
+                (ContentPart::Variable("score".into()), 21.348, 39.34),
+                (ContentPart::Text("to".into()), 64.024, 10.008),
+                (ContentPart::Value("10".into()), 77.368, 23.344),
+                (ContentPart::Text("and".into()), 104.048, 20.016),
+            ],
+            vec![
+                (ContentPart::Dropdown("look 1".into()), 0.0, 52.024),
+                (ContentPart::Text("now".into()), 55.36, 22.008),
+            ],
+        ];
+        assert_eq!(layout.lines.len(), expected.len());
+        for (line, expected) in layout.lines.iter().zip(expected) {
+            assert_eq!(line.parts.len(), expected.len());
+            for (placed, (part, x, width)) in line.parts.iter().zip(expected) {
+                assert_eq!(placed.part, part);
+                assert_close(placed.x, x);
+                assert_close(placed.width, width);
+            }
+        }
+        assert_close(layout.lines[0].width, 124.064);
+        assert_close(layout.lines[1].width, 77.368);
+    }
+
+    #[test]
+    fn bricks_grow_to_fit_their_content() {
+        let words = |count: usize| vec!["word"; count].join(" ");
+        assert_eq!(
+            fit_brick_type(BrickType::H1Base, "Play", None),
+            BrickType::H1Base
+        );
+        assert_eq!(
+            fit_brick_type(BrickType::H1Base, &words(30), None),
+            BrickType::H2Base
+        );
+        assert_eq!(
+            fit_brick_type(BrickType::H1Base, &words(60), None),
+            BrickType::H3Base
+        );
+        // the tallest variant is used even when the content overflows it
+        assert_eq!(
+            fit_brick_type(BrickType::H1Base, &words(500), None),
+            BrickType::H3Base
+        );
+        // never smaller than requested
+        assert_eq!(
+            fit_brick_type(BrickType::H2Base, "Play", None),
+            BrickType::H2Base
+        );
+        // fits one brick width but not a narrower one
+        assert_eq!(
+            fit_brick_type(BrickType::H1Base, &words(12), None),
+            BrickType::H1Base
+        );
+        assert_eq!(
+            fit_brick_type(BrickType::H1Base, &words(12), Some(200.0)),
+            BrickType::H2Base
+        );
+
+        assert_eq!(
+            fit_brick_type(BrickType::H1Control, "If", None),
+            BrickType::H1Control
+        );
+        assert_eq!(
+            fit_brick_type(BrickType::H1Control, &words(20), None),
+            BrickType::H2Control
+        );
+        // other bricks have a single height
+        assert_eq!(
+            fit_brick_type(BrickType::If, &words(60), None),
+            BrickType::If
+        );
+    }
+}
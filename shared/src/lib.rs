@@ -1,3 +1,17 @@
 pub mod brick;
+pub mod catalog;
+pub mod category;
+#[cfg(feature = "catrobat")]
+pub mod catrobat;
 pub mod color;
+pub mod content;
+pub mod document;
+pub mod editor;
+pub mod format;
+pub mod layout;
+pub mod raster;
+pub mod script;
+pub mod session;
+pub mod share;
+pub mod svg;
 pub mod types;
@@ -0,0 +1,257 @@
+#[cfg(feature = "png")]
+use crate::brick::base::BrickSVG;
+#[cfg(feature = "png")]
+use crate::color::ColorScheme;
+use std::fmt;
+
+// SVG user units are CSS pixels, which are defined at 96 DPI.
+pub const CSS_DPI: f32 = 96.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PngOptions {
+    // target width in pixels, takes precedence over the DPI based scale
+    pub width: Option<u32>,
+    // resolution of the image, also written to the PNG metadata when no
+    // width is given
+    pub dpi: Option<f32>,
+}
+
+impl PngOptions {
+    pub fn scale(&self, svg_width: f32) -> f32 {
+        match (self.width, self.dpi) {
+            (Some(width), _) => width as f32 / svg_width,
+            (None, Some(dpi)) => dpi / CSS_DPI,
+            (None, None) => 1.0,
+        }
+    }
+
+    pub fn pixel_size(&self, (width, height): (f32, f32)) -> (u32, u32) {
+        let scale = self.scale(width);
+        let pixels = |value: f32| ((value * scale).round() as u32).max(1);
+        (pixels(width), pixels(height))
+    }
+
+    // The resolution to record in the PNG. Only a DPI that set the scale is
+    // written, with a width it would not match the pixels.
+    pub fn metadata_dpi(&self) -> Option<f32> {
+        self.dpi.filter(|_| self.width.is_none())
+    }
+}
+
+#[derive(Debug)]
+pub enum RasterError {
+    InvalidOptions(String),
+    InvalidSvg(String),
+    Encode(String),
+}
+
+impl fmt::Display for RasterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidOptions(message) => write!(f, "invalid PNG options: {message}"),
+            Self::InvalidSvg(message) => write!(f, "could not parse SVG: {message}"),
+            Self::Encode(message) => write!(f, "could not encode PNG: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for RasterError {}
+
+// Upper bound for either side of the image, keeps a typo in the width from
+// allocating gigabytes.
+pub const MAX_PIXELS: u32 = 16_384;
+
+#[cfg(feature = "png")]
+pub fn render_png(
+    brick: &dyn BrickSVG,
+    color_scheme: &ColorScheme,
+    options: &PngOptions,
+) -> Result<Vec<u8>, RasterError> {
+    render_svg_png(&brick.to_svg_string(color_scheme), brick.size(), options)
+}
+
+// Rasterizes any SVG document whose viewBox is `size`, e.g. a whole script.
+#[cfg(feature = "png")]
+pub fn render_svg_png(
+    svg: &str,
+    size: (f32, f32),
+    options: &PngOptions,
+) -> Result<Vec<u8>, RasterError> {
+    use resvg::{tiny_skia, usvg};
+
+    if options.width == Some(0) {
+        return Err(RasterError::InvalidOptions("width must be positive".into()));
+    }
+    if options
+        .dpi
+        .is_some_and(|dpi| !dpi.is_finite() || dpi <= 0.0)
+    {
+        return Err(RasterError::InvalidOptions("dpi must be positive".into()));
+    }
+    let (width, height) = options.pixel_size(size);
+    if width > MAX_PIXELS || height > MAX_PIXELS {
+        return Err(RasterError::InvalidOptions(format!(
+            "{width}x{height} exceeds the maximum of {MAX_PIXELS} pixels per side"
+        )));
+    }
+
+    let (fontdb, font_family) = fonts();
+    let svg_options = usvg::Options {
+        fontdb: fontdb.clone(),
+        font_family: font_family.clone(),
+        ..usvg::Options::default()
+    };
+    let tree = usvg::Tree::from_str(svg, &svg_options)
+        .map_err(|error| RasterError::InvalidSvg(error.to_string()))?;
+
+    let mut pixmap = tiny_skia::Pixmap::new(width, height)
+        .ok_or_else(|| RasterError::InvalidOptions("empty image".into()))?;
+    let (svg_width, svg_height) = size;
+    let transform =
+        tiny_skia::Transform::from_scale(width as f32 / svg_width, height as f32 / svg_height);
+    resvg::render(&tree, transform, &mut pixmap.as_mut());
+
+    encode(&pixmap, options.metadata_dpi())
+}
+
+// System fonts are loaded once per process. Text without an explicit family
+// falls back to the first sans-serif face found, because the usvg default
+// (Times New Roman) is rarely installed on headless Linux machines.
+#[cfg(feature = "png")]
+fn fonts() -> &'static (std::sync::Arc<resvg::usvg::fontdb::Database>, String) {
+    use resvg::usvg::fontdb::Database;
+    use std::sync::{Arc, OnceLock};
+
+    static FONTS: OnceLock<(Arc<Database>, String)> = OnceLock::new();
+    FONTS.get_or_init(|| {
+        let mut database = Database::new();
+        database.load_system_fonts();
+        let families: Vec<&str> = database
+            .faces()
+            .filter_map(|face| face.families.first())
+            .map(|(family, _)| family.as_str())
+            .collect();
+        let fallback = families
+            .iter()
+            .find(|family| family.contains("Sans") && !family.contains("Mono"))
+            .or_else(|| families.first())
+            .map_or_else(|| "sans-serif".to_string(), ToString::to_string);
+        database.set_sans_serif_family(fallback.clone());
+        (Arc::new(database), fallback)
+    })
+}
+
+#[cfg(feature = "png")]
+fn encode(pixmap: &resvg::tiny_skia::Pixmap, dpi: Option<f32>) -> Result<Vec<u8>, RasterError> {
+    let data: Vec<u8> = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, pixmap.width(), pixmap.height());
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    if let Some(dpi) = dpi {
+        // pHYs stores pixels per meter
+        let pixels_per_meter = (dpi / 0.0254).round() as u32;
+        encoder.set_pixel_dims(Some(png::PixelDimensions {
+            xppu: pixels_per_meter,
+            yppu: pixels_per_meter,
+            unit: png::Unit::Meter,
+        }));
+    }
+
+    let mut writer = encoder
+        .write_header()
+        .map_err(|error| RasterError::Encode(error.to_string()))?;
+    writer
+        .write_image_data(&data)
+        .map_err(|error| RasterError::Encode(error.to_string()))?;
+    writer
+        .finish()
+        .map_err(|error| RasterError::Encode(error.to_string()))?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: (f32, f32) = (348.181, 72.95);
+
+    fn options(width: Option<u32>, dpi: Option<f32>) -> PngOptions {
+        PngOptions { width, dpi }
+    }
+
+    #[test]
+    fn pixel_sizes_follow_the_options() {
+        assert_eq!(options(None, None).pixel_size(SIZE), (348, 73));
+        assert_eq!(
+            options(None, Some(2.0 * CSS_DPI)).pixel_size(SIZE),
+            (696, 146)
+        );
+        assert_eq!(options(Some(100), None).pixel_size(SIZE), (100, 21));
+        // the width wins over the DPI
+        assert_eq!(options(Some(100), Some(300.0)).pixel_size(SIZE), (100, 21));
+        // never less than a pixel
+        assert_eq!(options(None, None).pixel_size((0.1, 0.0)), (1, 1));
+    }
+
+    #[test]
+    fn only_a_dpi_that_sets_the_scale_is_recorded() {
+        assert_eq!(options(None, Some(300.0)).metadata_dpi(), Some(300.0));
+        assert_eq!(options(Some(100), Some(300.0)).metadata_dpi(), None);
+        assert_eq!(options(Some(100), None).metadata_dpi(), None);
+    }
+
+    #[cfg(feature = "png")]
+    fn render(options: &PngOptions) -> Result<png::OutputInfo, RasterError> {
+        let svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {} {}"><rect width="10" height="10"/></svg>"#,
+            SIZE.0, SIZE.1
+        );
+        let bytes = render_svg_png(&svg, SIZE, options)?;
+        let mut reader = png::Decoder::new(std::io::Cursor::new(bytes))
+            .read_info()
+            .unwrap();
+        let dims = reader.info().pixel_dims;
+        let mut data = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut data).unwrap();
+        assert_eq!(dims.is_some(), options.metadata_dpi().is_some());
+        Ok(info)
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn pngs_have_the_computed_size() {
+        let info = render(&options(None, Some(2.0 * CSS_DPI))).unwrap();
+        assert_eq!((info.width, info.height), (696, 146));
+        let info = render(&options(Some(100), Some(2.0 * CSS_DPI))).unwrap();
+        assert_eq!((info.width, info.height), (100, 21));
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn invalid_options_are_rejected() {
+        for options in [
+            options(Some(0), None),
+            options(None, Some(0.0)),
+            options(None, Some(-96.0)),
+            options(None, Some(f32::NAN)),
+            options(None, Some(f32::INFINITY)),
+            options(Some(MAX_PIXELS + 1), None),
+            // 348 units at 5000 DPI are about 18,000 pixels
+            options(None, Some(5000.0)),
+        ] {
+            assert!(
+                matches!(render(&options), Err(RasterError::InvalidOptions(_))),
+                "{options:?}"
+            );
+        }
+    }
+}
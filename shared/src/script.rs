@@ -0,0 +1,469 @@
+use crate::brick::base::{BrickSVG, svg_root};
+use crate::brick::c_block::INNER_X;
+use crate::brick::geometry::BOTTOM_OFFSET;
+use crate::color::ColorScheme;
+use crate::document::BrickSpec;
+use crate::svg::SvgElement;
+use serde::{Deserialize, Serialize};
+use yew::Html;
+
+// A script is a stack of bricks read from top to bottom. C-shaped bricks hold
+// their own stacks in their slots. The whole script renders as one SVG with
+// every connector nub sitting in the notch of the brick below.
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScriptBrick {
+    #[serde(flatten)]
+    pub brick: BrickSpec,
+    // bricks nested in the slots of C-shaped bricks, one stack per slot
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub slots: Vec<Vec<ScriptBrick>>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Script {
+    #[serde(default)]
+    pub bricks: Vec<ScriptBrick>,
+}
+
+// Where a brick sits in the script: the stack it belongs to, given as the
+// (brick index, slot index) steps from the top level down to a slot, and its
+// index in that stack.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BrickPosition {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stack: Vec<(usize, usize)>,
+    pub index: usize,
+}
+
+impl BrickPosition {
+    pub const fn top(index: usize) -> Self {
+        Self {
+            stack: Vec::new(),
+            index,
+        }
+    }
+
+    // first position in the given slot of this brick
+    #[must_use]
+    pub fn slot(&self, slot: usize) -> Self {
+        let mut stack = self.stack.clone();
+        stack.push((self.index, slot));
+        Self { stack, index: 0 }
+    }
+
+    #[must_use]
+    pub fn with_index(&self, index: usize) -> Self {
+        Self {
+            stack: self.stack.clone(),
+            index,
+        }
+    }
+
+    // whether `other` is this brick or one nested in it
+    pub fn contains(&self, other: &Self) -> bool {
+        let depth = self.stack.len();
+        other.stack.len() >= depth
+            && other.stack[..depth] == self.stack[..]
+            && match other.stack.get(depth) {
+                Some((index, _)) => *index == self.index,
+                None => other.index == self.index,
+            }
+    }
+
+    // where `other` ends up once the brick at this position is removed
+    #[must_use]
+    pub fn after_removal(&self, other: &Self) -> Self {
+        let depth = self.stack.len();
+        let mut other = other.clone();
+        if other.stack.len() > depth && other.stack[..depth] == self.stack[..] {
+            if other.stack[depth].0 > self.index {
+                other.stack[depth].0 -= 1;
+            }
+        } else if other.stack == self.stack && other.index > self.index {
+            other.index -= 1;
+        }
+        other
+    }
+}
+
+// a brick of the script with its position in the script image
+pub struct PlacedBrick {
+    pub brick: Box<dyn BrickSVG>,
+    pub color_scheme: ColorScheme,
+    pub position: BrickPosition,
+    pub x: f32,
+    pub y: f32,
+}
+
+// A place a brick can be dropped at: above the brick at `position`, or at the
+// end of its stack. `y` is the border the dropped brick would start at.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DropPoint {
+    pub position: BrickPosition,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+}
+
+#[derive(Default)]
+pub struct ScriptLayout {
+    pub bricks: Vec<PlacedBrick>,
+    pub drop_points: Vec<DropPoint>,
+    // stacked height of the top level, without the nub of the last brick
+    pub height: f32,
+}
+
+impl ScriptLayout {
+    // width and height of the script image in SVG user units
+    pub fn size(&self) -> (f32, f32) {
+        self.bricks
+            .iter()
+            .fold((0.0, 0.0), |(width, height), placed| {
+                let (brick_width, brick_height) = placed.brick.size();
+                (
+                    width.max(placed.x + brick_width),
+                    height.max(placed.y + brick_height),
+                )
+            })
+    }
+
+    fn offset(mut self, x: f32, y: f32) -> Self {
+        for placed in &mut self.bricks {
+            placed.x += x;
+            placed.y += y;
+        }
+        for point in &mut self.drop_points {
+            point.x += x;
+            point.y += y;
+        }
+        self
+    }
+}
+
+impl ScriptBrick {
+    pub const fn new(brick: BrickSpec) -> Self {
+        Self {
+            brick,
+            slots: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_slot(mut self, bricks: Vec<Self>) -> Self {
+        self.slots.push(bricks);
+        self
+    }
+}
+
+// Places the bricks of the stack at `stack` below each other, starting at the
+// origin. Bricks without an explicit width take `width`, so nested bricks fit
+// their slot.
+fn stack(bricks: &[ScriptBrick], stack: &[(usize, usize)], width: Option<f32>) -> ScriptLayout {
+    let mut layout = ScriptLayout::default();
+    let mut y = 0.0;
+    let mut widest = width.unwrap_or(0.0);
+    for (index, script_brick) in bricks.iter().enumerate() {
+        let position = BrickPosition {
+            stack: stack.to_vec(),
+            index,
+        };
+        let spec = &script_brick.brick;
+        let mut brick = match (spec.width, width) {
+            (None, Some(width)) => spec.clone().with_size(Some(width), spec.height).to_brick(),
+            _ => spec.to_brick(),
+        };
+        let brick_width = brick.size().0;
+        widest = widest.max(brick_width);
+        // stacks in slots the brick does not have are not drawn
+        let slot_count = brick.slots().len();
+        let slots: Vec<ScriptLayout> = (0..slot_count)
+            .map(|slot| {
+                let bricks = script_brick.slots.get(slot).map_or(&[][..], Vec::as_slice);
+                let mut path = stack.to_vec();
+                path.push((index, slot));
+                self::stack(bricks, &path, Some(brick_width - INNER_X))
+            })
+            .collect();
+        let heights: Vec<f32> = slots.iter().map(|slot| slot.height).collect();
+        brick.set_slot_heights(&heights);
+
+        let height = brick.size().1;
+        let slot_origins = brick.slots();
+        layout.drop_points.push(DropPoint {
+            position: position.clone(),
+            x: 0.0,
+            y,
+            width: brick_width,
+        });
+        layout.bricks.push(PlacedBrick {
+            brick,
+            color_scheme: spec.color_scheme.clone(),
+            position,
+            x: 0.0,
+            y,
+        });
+        for (slot, origin) in slots.into_iter().zip(slot_origins) {
+            let slot = slot.offset(origin.x, y + origin.y);
+            layout.bricks.extend(slot.bricks);
+            layout.drop_points.extend(slot.drop_points);
+        }
+        y += height - BOTTOM_OFFSET;
+    }
+    layout.drop_points.push(DropPoint {
+        position: BrickPosition {
+            stack: stack.to_vec(),
+            index: bricks.len(),
+        },
+        x: 0.0,
+        y,
+        width: widest,
+    });
+    layout.height = y;
+    layout
+}
+
+impl Script {
+    pub const fn new(bricks: Vec<ScriptBrick>) -> Self {
+        Self { bricks }
+    }
+
+    pub fn layout(&self) -> ScriptLayout {
+        stack(&self.bricks, &[], None)
+    }
+
+    // width and height of the script image in SVG user units
+    pub fn size(&self) -> (f32, f32) {
+        self.layout().size()
+    }
+
+    pub fn render(&self) -> SvgElement {
+        let layout = self.layout();
+        let (width, height) = layout.size();
+        // every brick brings its own style and definitions, scoped so the
+        // color scheme of one brick does not leak into the others
+        svg_root(width, height).children(layout.bricks.iter().enumerate().map(|(index, placed)| {
+            let (width, height) = placed.brick.size();
+            placed
+                .brick
+                .render(&placed.color_scheme)
+                .attr("x", placed.x.to_string())
+                .attr("y", placed.y.to_string())
+                .attr("width", width.to_string())
+                .attr("height", height.to_string())
+                .scoped(&format!("brick{index}"))
+        }))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bricks.is_empty()
+    }
+
+    fn stack(&self, path: &[(usize, usize)]) -> Option<&Vec<ScriptBrick>> {
+        path.iter().try_fold(&self.bricks, |bricks, (index, slot)| {
+            bricks.get(*index)?.slots.get(*slot)
+        })
+    }
+
+    fn stack_mut(&mut self, path: &[(usize, usize)]) -> Option<&mut Vec<ScriptBrick>> {
+        path.iter()
+            .try_fold(&mut self.bricks, |bricks, (index, slot)| {
+                bricks.get_mut(*index)?.slots.get_mut(*slot)
+            })
+    }
+
+    // Like `stack_mut`, but creates missing slots on the way, so bricks can be
+    // dropped into an empty C-shaped brick. Only slots the brick draws are
+    // created.
+    fn insertion_stack(&mut self, path: &[(usize, usize)]) -> Option<&mut Vec<ScriptBrick>> {
+        path.iter()
+            .try_fold(&mut self.bricks, |bricks, (index, slot)| {
+                let script_brick = bricks.get_mut(*index)?;
+                if script_brick.slots.len() <= *slot {
+                    if script_brick.brick.to_brick().slots().len() <= *slot {
+                        return None;
+                    }
+                    script_brick.slots.resize_with(slot + 1, Vec::new);
+                }
+                Some(&mut script_brick.slots[*slot])
+            })
+    }
+
+    pub fn get(&self, position: &BrickPosition) -> Option<&ScriptBrick> {
+        self.stack(&position.stack)?.get(position.index)
+    }
+
+    pub fn get_mut(&mut self, position: &BrickPosition) -> Option<&mut ScriptBrick> {
+        self.stack_mut(&position.stack)?.get_mut(position.index)
+    }
+
+    // Inserts the brick before the one at `position`, or at the end of the
+    // stack. Returns false when the stack does not exist.
+    pub fn insert(&mut self, position: &BrickPosition, brick: ScriptBrick) -> bool {
+        let Some(bricks) = self.insertion_stack(&position.stack) else {
+            return false;
+        };
+        bricks.insert(position.index.min(bricks.len()), brick);
+        true
+    }
+
+    // removes the brick together with the bricks nested in it
+    pub fn remove(&mut self, position: &BrickPosition) -> Option<ScriptBrick> {
+        let bricks = self.stack_mut(&position.stack)?;
+        (position.index < bricks.len()).then(|| bricks.remove(position.index))
+    }
+
+    // Moves a brick with its nested bricks so it ends up before the brick that
+    // is at `to` now. Returns the new position of the brick, or None when the
+    // move is impossible, e.g. into the brick's own slots.
+    pub fn move_brick(
+        &mut self,
+        from: &BrickPosition,
+        to: &BrickPosition,
+    ) -> Option<BrickPosition> {
+        if from.contains(to) && to != from {
+            return None;
+        }
+        let to = from.after_removal(to);
+        let brick = self.remove(from)?;
+        let Some(bricks) = self.insertion_stack(&to.stack) else {
+            self.insert(from, brick);
+            return None;
+        };
+        let to = to.with_index(to.index.min(bricks.len()));
+        bricks.insert(to.index, brick);
+        Some(to)
+    }
+
+    pub fn to_svg(&self) -> Html {
+        self.render().to_html()
+    }
+
+    pub fn to_svg_string(&self) -> String {
+        self.render().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::BLUE_SCHEME;
+    use crate::types::BrickType;
+
+    fn brick(content: &str) -> ScriptBrick {
+        ScriptBrick::new(BrickSpec::new(BrickType::H1Base, BLUE_SCHEME, content))
+    }
+
+    fn if_brick(slot: Vec<ScriptBrick>) -> ScriptBrick {
+        ScriptBrick::new(BrickSpec::new(BrickType::If, BLUE_SCHEME, "If")).with_slot(slot)
+    }
+
+    fn contents(bricks: &[ScriptBrick]) -> Vec<&str> {
+        bricks
+            .iter()
+            .map(|script_brick| script_brick.brick.content.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn lookups_and_removals_create_no_slots() {
+        let mut script = Script::new(vec![brick("a"), if_brick(Vec::new())]);
+        script.bricks[1].slots.clear();
+        let into_a = BrickPosition::top(0).slot(0);
+        let into_if = BrickPosition::top(1).slot(0);
+
+        assert!(script.get_mut(&into_a).is_none());
+        assert!(script.remove(&into_a).is_none());
+        assert!(script.get_mut(&into_if).is_none());
+        assert!(script.remove(&into_if).is_none());
+        assert!(
+            script
+                .bricks
+                .iter()
+                .all(|script_brick| script_brick.slots.is_empty())
+        );
+
+        // only C-shaped bricks take bricks, in the slots they have
+        assert!(!script.insert(&into_a, brick("b")));
+        assert!(!script.insert(&BrickPosition::top(1).slot(1), brick("b")));
+        assert!(script.insert(&into_if, brick("b")));
+        assert!(script.bricks[0].slots.is_empty());
+        assert_eq!(contents(&script.bricks[1].slots[0]), ["b"]);
+
+        let json = serde_json::to_string(&script.bricks[0]).unwrap();
+        assert!(!json.contains("slots"), "{json}");
+    }
+
+    #[test]
+    fn moves_within_a_stack() {
+        let mut script = Script::new(vec![brick("a"), brick("b"), brick("c"), brick("d")]);
+        // before the brick that is at 3 now, which moves up by one
+        let moved = script.move_brick(&BrickPosition::top(0), &BrickPosition::top(3));
+        assert_eq!(moved, Some(BrickPosition::top(2)));
+        assert_eq!(contents(&script.bricks), ["b", "c", "a", "d"]);
+
+        let moved = script.move_brick(&BrickPosition::top(3), &BrickPosition::top(0));
+        assert_eq!(moved, Some(BrickPosition::top(0)));
+        assert_eq!(contents(&script.bricks), ["d", "b", "c", "a"]);
+
+        // to the end, and onto itself
+        let moved = script.move_brick(&BrickPosition::top(1), &BrickPosition::top(4));
+        assert_eq!(moved, Some(BrickPosition::top(3)));
+        assert_eq!(contents(&script.bricks), ["d", "c", "a", "b"]);
+        let moved = script.move_brick(&BrickPosition::top(2), &BrickPosition::top(2));
+        assert_eq!(moved, Some(BrickPosition::top(2)));
+        assert_eq!(contents(&script.bricks), ["d", "c", "a", "b"]);
+    }
+
+    #[test]
+    fn moves_into_and_out_of_slots() {
+        let mut script = Script::new(vec![brick("a"), if_brick(vec![brick("x")]), brick("b")]);
+        // the C-shaped brick moves up once "a" is taken out
+        let moved = script.move_brick(
+            &BrickPosition::top(0),
+            &BrickPosition::top(1).slot(0).with_index(1),
+        );
+        let in_slot = BrickPosition::top(0).slot(0).with_index(1);
+        assert_eq!(moved, Some(in_slot.clone()));
+        assert_eq!(contents(&script.bricks), ["If", "b"]);
+        assert_eq!(contents(&script.bricks[0].slots[0]), ["x", "a"]);
+
+        let moved = script.move_brick(&in_slot, &BrickPosition::top(2));
+        assert_eq!(moved, Some(BrickPosition::top(2)));
+        assert_eq!(contents(&script.bricks), ["If", "b", "a"]);
+        assert_eq!(contents(&script.bricks[0].slots[0]), ["x"]);
+
+        // from the top level into the slot of a brick below
+        let mut script = Script::new(vec![brick("a"), brick("b"), if_brick(Vec::new())]);
+        let moved = script.move_brick(&BrickPosition::top(1), &BrickPosition::top(2).slot(0));
+        assert_eq!(moved, Some(BrickPosition::top(1).slot(0)));
+        assert_eq!(contents(&script.bricks), ["a", "If"]);
+        assert_eq!(contents(&script.bricks[1].slots[0]), ["b"]);
+    }
+
+    #[test]
+    fn bricks_do_not_move_into_their_own_slots() {
+        let inner = if_brick(vec![brick("x")]);
+        let mut script = Script::new(vec![if_brick(vec![inner]), brick("b")]);
+        let before = script.clone();
+        let own_slot = BrickPosition::top(0).slot(0);
+        let nested_slot = own_slot.slot(0).with_index(1);
+
+        assert!(BrickPosition::top(0).contains(&nested_slot));
+        assert!(!BrickPosition::top(1).contains(&nested_slot));
+        assert_eq!(script.move_brick(&BrickPosition::top(0), &own_slot), None);
+        assert_eq!(
+            script.move_brick(&BrickPosition::top(0), &nested_slot),
+            None
+        );
+        assert_eq!(script.move_brick(&own_slot, &nested_slot), None);
+        assert_eq!(script, before);
+
+        // a slot that does not exist leaves the brick where it was
+        assert_eq!(
+            script.move_brick(&BrickPosition::top(1), &BrickPosition::top(0).slot(1)),
+            None
+        );
+        assert_eq!(script, before);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::color::ColorScheme;
+use crate::document::DocumentError;
+use crate::editor::EditorState;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+// Editor sessions as the frontend keeps them between reloads. Every saved
+// session carries the version of its format. Older versions are migrated to
+// the current one when they are read, newer ones are rejected so an old build
+// starts with defaults instead of misreading them.
+//
+// 1: the editor state, i.e. the panel brick, the script and the selection
+pub const SESSION_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct SavedSession<'a> {
+    version: u32,
+    state: &'a EditorState,
+}
+
+#[derive(Deserialize)]
+struct SessionHeader {
+    version: u32,
+}
+
+#[derive(Deserialize)]
+struct SessionV1 {
+    state: EditorState,
+}
+
+fn parse<T: DeserializeOwned>(text: &str) -> Result<T, DocumentError> {
+    serde_json::from_str(text).map_err(|error| DocumentError::Parse(error.to_string()))
+}
+
+// the version of a saved session or color scheme list, read before the rest
+fn version(text: &str) -> Result<u32, DocumentError> {
+    Ok(parse::<SessionHeader>(text)?.version)
+}
+
+pub fn save_session(state: &EditorState) -> Result<String, DocumentError> {
+    serde_json::to_string(&SavedSession {
+        version: SESSION_VERSION,
+        state,
+    })
+    .map_err(|error| DocumentError::Serialize(error.to_string()))
+}
+
+pub fn load_session(text: &str) -> Result<EditorState, DocumentError> {
+    // a new version adds its own arm and migrates the previous format here
+    match version(text)? {
+        1 => Ok(parse::<SessionV1>(text)?.state),
+        version => Err(DocumentError::UnsupportedVersion(version)),
+    }
+}
+
+// The color schemes a user defined, kept next to the session so they survive
+// a reset of the editor. Versioned like the session.
+//
+// 1: the list of custom schemes
+pub const COLOR_SCHEMES_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct SavedColorSchemes<'a> {
+    version: u32,
+    schemes: &'a [ColorScheme],
+}
+
+#[derive(Deserialize)]
+struct ColorSchemesV1 {
+    schemes: Vec<ColorScheme>,
+}
+
+pub fn save_color_schemes(schemes: &[ColorScheme]) -> Result<String, DocumentError> {
+    serde_json::to_string(&SavedColorSchemes {
+        version: COLOR_SCHEMES_VERSION,
+        schemes,
+    })
+    .map_err(|error| DocumentError::Serialize(error.to_string()))
+}
+
+pub fn load_color_schemes(text: &str) -> Result<Vec<ColorScheme>, DocumentError> {
+    match version(text)? {
+        1 => Ok(parse::<ColorSchemesV1>(text)?.schemes),
+        version => Err(DocumentError::UnsupportedVersion(version)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::{BLUE_SCHEME, Color, DARK_BLUE_SCHEME, ORANGE_SCHEME};
+    use crate::document::BrickSpec;
+    use crate::script::{BrickPosition, Script, ScriptBrick};
+    use crate::types::BrickType;
+
+    #[test]
+    fn sessions_round_trip() {
+        let brick = BrickSpec::new(BrickType::H1Base, ORANGE_SCHEME, "Play sound [meow]");
+        let state = EditorState {
+            brick: brick.clone(),
+            script: Script::new(vec![ScriptBrick::new(brick)]),
+            selected: Some(BrickPosition::top(0)),
+        };
+        let saved = save_session(&state).unwrap();
+        assert!(saved.starts_with(&format!(r#"{{"version":{SESSION_VERSION},"#)));
+        assert_eq!(load_session(&saved).unwrap(), state);
+
+        let schemes = vec![ColorScheme::derive("Mint", Color::hex("#2a9d8f"))];
+        let saved = save_color_schemes(&schemes).unwrap();
+        assert_eq!(load_color_schemes(&saved).unwrap(), schemes);
+    }
+
+    #[test]
+    fn newer_versions_are_rejected() {
+        let session = format!(r#"{{"version": {}, "state": {{}}}}"#, SESSION_VERSION + 1);
+        assert!(matches!(
+            load_session(&session),
+            Err(DocumentError::UnsupportedVersion(version)) if version == SESSION_VERSION + 1
+        ));
+        let schemes = format!(
+            r#"{{"version": {}, "schemes": []}}"#,
+            COLOR_SCHEMES_VERSION + 1
+        );
+        assert!(matches!(
+            load_color_schemes(&schemes),
+            Err(DocumentError::UnsupportedVersion(_))
+        ));
+        assert!(matches!(load_session("{}"), Err(DocumentError::Parse(_))));
+        assert!(matches!(
+            load_session("not json"),
+            Err(DocumentError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn schemes_stored_by_name_still_load() {
+        // sessions from before custom schemes named the built-in ones
+        let saved = r#"{"version": 1, "state": {
+            "brick": {"type": "H1Base", "scheme": "Dark Blue", "content": "Hello"},
+            "script": {"bricks": [{"type": "If", "scheme": "blue", "slots": [[]]}]},
+            "selected": {"index": 0}
+        }}"#;
+        let state = load_session(saved).unwrap();
+        assert_eq!(state.brick.color_scheme, DARK_BLUE_SCHEME);
+        assert_eq!(state.script.bricks[0].brick.color_scheme, BLUE_SCHEME);
+        assert_eq!(state.selected, Some(BrickPosition::top(0)));
+    }
+}
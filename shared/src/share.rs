@@ -0,0 +1,129 @@
+use crate::document::DocumentError;
+use crate::editor::EditorState;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+
+// Share links carry the brick and the script in the URL fragment:
+//
+//   https://example.org/BrickCreator/#v1.<payload>
+//
+// The payload is the editor state as JSON, deflated and encoded as URL safe
+// base64 without padding. The fragment never reaches the server, so links
+// work whatever path the frontend is deployed under.
+
+const PREFIX: &str = "v";
+pub const SHARE_VERSION: u32 = 1;
+// inflated states larger than this are rejected
+const MAX_STATE_BYTES: usize = 1 << 20;
+
+// The fragment for the state, without the leading '#'. The selection is not
+// shared.
+pub fn encode_share(state: &EditorState) -> Result<String, DocumentError> {
+    let state = EditorState {
+        selected: None,
+        ..state.clone()
+    };
+    let json =
+        serde_json::to_vec(&state).map_err(|error| DocumentError::Serialize(error.to_string()))?;
+    let deflated = miniz_oxide::deflate::compress_to_vec(&json, 9);
+    Ok(format!(
+        "{PREFIX}{SHARE_VERSION}.{}",
+        URL_SAFE_NO_PAD.encode(deflated)
+    ))
+}
+
+// Reads a fragment written by `encode_share`, with or without the '#'.
+pub fn decode_share(fragment: &str) -> Result<EditorState, DocumentError> {
+    let fragment = fragment.strip_prefix('#').unwrap_or(fragment);
+    let (version, payload) = fragment
+        .strip_prefix(PREFIX)
+        .and_then(|rest| rest.split_once('.'))
+        .ok_or_else(|| DocumentError::Parse("not a share link".into()))?;
+    let version: u32 = version
+        .parse()
+        .map_err(|_| DocumentError::Parse(format!("invalid share link version '{version}'")))?;
+    if version != SHARE_VERSION {
+        return Err(DocumentError::UnsupportedVersion(version));
+    }
+
+    let deflated = URL_SAFE_NO_PAD
+        .decode(payload)
+        .map_err(|error| DocumentError::Parse(error.to_string()))?;
+    let json = miniz_oxide::inflate::decompress_to_vec_with_limit(&deflated, MAX_STATE_BYTES)
+        .map_err(|error| DocumentError::Parse(error.to_string()))?;
+    serde_json::from_slice(&json).map_err(|error| DocumentError::Parse(error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::RED_SCHEME;
+    use crate::document::BrickSpec;
+    use crate::script::{BrickPosition, Script, ScriptBrick};
+    use crate::types::BrickType;
+
+    fn state() -> EditorState {
+        let brick = BrickSpec::new(BrickType::H1Base, RED_SCHEME, "Move {10} steps");
+        EditorState {
+            brick: brick.clone().with_size(Some(300.0), None),
+            script: Script::new(vec![
+                ScriptBrick::new(BrickSpec::new(BrickType::Repeat, RED_SCHEME, "Repeat"))
+                    .with_slot(vec![ScriptBrick::new(brick)]),
+            ]),
+            selected: Some(BrickPosition::top(0)),
+        }
+    }
+
+    #[test]
+    fn states_round_trip_without_the_selection() {
+        let fragment = encode_share(&state()).unwrap();
+        assert!(fragment.starts_with(&format!("{PREFIX}{SHARE_VERSION}.")));
+        assert!(
+            fragment
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "._-".contains(c))
+        );
+
+        let expected = EditorState {
+            selected: None,
+            ..state()
+        };
+        assert_eq!(decode_share(&fragment).unwrap(), expected);
+        assert_eq!(decode_share(&format!("#{fragment}")).unwrap(), expected);
+    }
+
+    #[test]
+    fn malformed_links_are_rejected() {
+        let fragment = encode_share(&state()).unwrap();
+        let payload = fragment.split_once('.').unwrap().1;
+        let not_json = URL_SAFE_NO_PAD.encode(miniz_oxide::deflate::compress_to_vec(b"{", 9));
+        for malformed in [
+            String::new(),
+            "#".to_string(),
+            "brick".to_string(),
+            format!("v1{payload}"),
+            format!("vx.{payload}"),
+            "v1.not base64!".to_string(),
+            format!("v1.{}", URL_SAFE_NO_PAD.encode(b"not deflated")),
+            format!("v1.{not_json}"),
+            format!("v1.{}", &payload[..payload.len() / 2]),
+        ] {
+            assert!(
+                matches!(decode_share(&malformed), Err(DocumentError::Parse(_))),
+                "{malformed}"
+            );
+        }
+    }
+
+    #[test]
+    fn other_versions_are_rejected() {
+        let fragment = encode_share(&state()).unwrap();
+        let payload = fragment.split_once('.').unwrap().1;
+        for version in [0, SHARE_VERSION + 1] {
+            assert!(matches!(
+                decode_share(&format!("v{version}.{payload}")),
+                Err(DocumentError::UnsupportedVersion(found)) if found == version
+            ));
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use std::fmt;
+use yew::Html;
+use yew::virtual_dom::{VNode, VTag, VText};
+
+// A small, framework independent SVG element tree.
+// Bricks build their shape with it, and the tree is either serialized to a
+// plain SVG string or converted into Yew nodes for the frontend.
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SvgNode {
+    Element(SvgElement),
+    Text(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SvgElement {
+    pub tag: &'static str,
+    pub attributes: Vec<(&'static str, String)>,
+    pub children: Vec<SvgNode>,
+}
+
+impl SvgElement {
+    pub const fn new(tag: &'static str) -> Self {
+        Self {
+            tag,
+            attributes: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn attr(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.attributes.push((name, value.into()));
+        self
+    }
+
+    #[must_use]
+    pub fn child(mut self, child: impl Into<SvgNode>) -> Self {
+        self.children.push(child.into());
+        self
+    }
+
+    #[must_use]
+    pub fn children<I>(mut self, children: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<SvgNode>,
+    {
+        self.children.extend(children.into_iter().map(Into::into));
+        self
+    }
+
+    #[must_use]
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.children.push(SvgNode::Text(text.into()));
+        self
+    }
+
+    pub fn get_attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    // Makes ids and style rules local to this element, so several bricks can
+    // share one document. Ids and the references to them get `scope` as a
+    // prefix, and style rules only match below an element with id `scope`.
+    #[must_use]
+    pub fn scoped(mut self, scope: &str) -> Self {
+        self.scope_children(scope);
+        self.attributes.retain(|(name, _)| *name != "id");
+        self.attr("id", scope)
+    }
+
+    fn scope_children(&mut self, scope: &str) {
+        for (name, value) in &mut self.attributes {
+            *value = match *name {
+                "id" => format!("{scope}-{value}"),
+                "href" => value.replacen('#', &format!("#{scope}-"), 1),
+                _ => value.replace("url(#", &format!("url(#{scope}-")),
+            };
+        }
+        let is_style = self.tag == "style";
+        for child in &mut self.children {
+            match child {
+                SvgNode::Element(element) => element.scope_children(scope),
+                SvgNode::Text(css) if is_style => *css = scope_css(css, scope),
+                SvgNode::Text(_) => {}
+            }
+        }
+    }
+
+    pub fn to_html(&self) -> Html {
+        let mut tag = VTag::new(self.tag);
+        for (name, value) in &self.attributes {
+            tag.add_attribute(name, value.clone());
+        }
+        tag.add_children(self.children.iter().map(SvgNode::to_html));
+        VNode::from(tag)
+    }
+}
+
+impl SvgNode {
+    pub fn to_html(&self) -> Html {
+        match self {
+            Self::Element(element) => element.to_html(),
+            Self::Text(text) => VNode::from(VText::new(text.clone())),
+        }
+    }
+}
+
+impl From<SvgElement> for SvgNode {
+    fn from(element: SvgElement) -> Self {
+        Self::Element(element)
+    }
+}
+
+impl From<&str> for SvgNode {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_string())
+    }
+}
+
+impl From<String> for SvgNode {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl fmt::Display for SvgElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<{}", self.tag)?;
+        for (name, value) in &self.attributes {
+            write!(f, " {}=\"{}\"", name, escape(value))?;
+        }
+        if self.children.is_empty() {
+            return write!(f, "/>");
+        }
+        write!(f, ">")?;
+        for child in &self.children {
+            write!(f, "{child}")?;
+        }
+        write!(f, "</{}>", self.tag)
+    }
+}
+
+impl fmt::Display for SvgNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Element(element) => element.fmt(f),
+            Self::Text(text) => f.write_str(&escape(text)),
+        }
+    }
+}
+
+// prefixes every selector of the rules with `#scope`
+fn scope_css(css: &str, scope: &str) -> String {
+    css.split_inclusive('}')
+        .map(|rule| {
+            let Some((selectors, body)) = rule.split_once('{') else {
+                return rule.to_string();
+            };
+            let selectors: Vec<String> = selectors
+                .split(',')
+                .map(|selector| format!("#{scope} {}", selector.trim()))
+                .collect();
+            let indent = &rule[..rule.len() - rule.trim_start().len()];
+            format!("{indent}{} {{{body}", selectors.join(", "))
+        })
+        .collect()
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
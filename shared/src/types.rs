@@ -1,3 +1,26 @@
+// Implements Serialize and Deserialize for a type with `id` and `from_id`.
+// Values are stored by their id, and parsing accepts any letter case like
+// `from_id` does.
+macro_rules! serde_by_id {
+    ($type:ty, $kind:literal) => {
+        impl serde::Serialize for $type {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.id())
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $type {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let id = <String as serde::Deserialize>::deserialize(deserializer)?;
+                Self::from_id(&id).ok_or_else(|| {
+                    serde::de::Error::custom(format!(concat!("unknown ", $kind, " '{}'"), id))
+                })
+            }
+        }
+    };
+}
+pub(crate) use serde_by_id;
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum BrickType {
     H0Collapsed,
@@ -6,4 +29,49 @@ pub enum BrickType {
     H3Base,
     H1Control,
     H2Control,
+    // C-shaped bricks with inner slots
+    If,
+    IfElse,
+    Repeat,
+    Forever,
+}
+
+pub const ALL_BRICK_TYPES: &[BrickType] = &[
+    BrickType::H0Collapsed,
+    BrickType::H1Base,
+    BrickType::H1Control,
+    BrickType::H2Base,
+    BrickType::H2Control,
+    BrickType::H3Base,
+    BrickType::If,
+    BrickType::IfElse,
+    BrickType::Repeat,
+    BrickType::Forever,
+];
+
+impl BrickType {
+    // stable identifier used by the backend and in files, e.g. "H1Base"
+    pub const fn id(self) -> &'static str {
+        match self {
+            Self::H0Collapsed => "H0Collapsed",
+            Self::H1Base => "H1Base",
+            Self::H2Base => "H2Base",
+            Self::H3Base => "H3Base",
+            Self::H1Control => "H1Control",
+            Self::H2Control => "H2Control",
+            Self::If => "If",
+            Self::IfElse => "IfElse",
+            Self::Repeat => "Repeat",
+            Self::Forever => "Forever",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        ALL_BRICK_TYPES
+            .iter()
+            .copied()
+            .find(|brick_type| brick_type.id().eq_ignore_ascii_case(id))
+    }
 }
+
+serde_by_id!(BrickType, "brick type");